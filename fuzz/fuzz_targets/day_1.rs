@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Any text must parse and solve to an answer or an error, never a panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        for part in 1..=2 {
+            let _ = aoc_2022::solve(1, part, input);
+        }
+    }
+});
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The answer to one part of a day's puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Answer {
+    Number(usize),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(number: usize) -> Self {
+        Answer::Number(number)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_displays_numbers_and_text() {
+        assert_eq!(Answer::from(42).to_string(), "42");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+    }
+}
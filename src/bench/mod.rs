@@ -0,0 +1,392 @@
+use super::runner::{self, Solver};
+use anyhow::{Context, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const BASELINE_DIR: &str = ".aoc/bench";
+/// The baseline every benchmark of the registered implementations updates.
+pub const LAST: &str = "last";
+
+/// Above this Welch's t statistic a difference is treated as real, which is
+/// roughly the 95% confidence level once there are a few dozen samples.
+const SIGNIFICANT_T: f64 = 2.0;
+
+/// Timing statistics for one phase of one day, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    pub day: usize,
+    /// `parse`, `part 1` or `part 2`.
+    pub phase: String,
+    pub samples: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Statistics {
+    pub fn from_durations(day: usize, phase: &str, durations: &[Duration]) -> Self {
+        let samples = durations.len();
+        let nanos: Vec<f64> = durations
+            .iter()
+            .map(|duration| duration.as_nanos() as f64)
+            .collect();
+        let mean = nanos.iter().sum::<f64>() / samples.max(1) as f64;
+        let variance = if samples > 1 {
+            nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (samples - 1) as f64
+        } else {
+            0.0
+        };
+        Self {
+            day,
+            phase: phase.to_string(),
+            samples,
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+}
+
+/// Parses and solves `input` `samples` times, collecting statistics for the
+/// parse phase, if the solver has one, and every part that produced an answer.
+pub fn benchmark(
+    day: usize,
+    solver: &dyn Solver,
+    input: &str,
+    samples: usize,
+) -> Result<Vec<Statistics>> {
+    // Warm up caches and the allocator before measuring.
+    solver.run_input(day, input);
+
+    let mut parse = Vec::new();
+    let mut parts: Vec<(usize, Vec<Duration>)> = Vec::new();
+    for _ in 0..samples {
+        let report = solver.run_input(day, input);
+        if let runner::Status::Failed(error) = report.status {
+            return Err(error);
+        }
+        parse.extend(report.parse);
+        for part in report.parts.iter().filter(|part| part.answer.is_ok()) {
+            match parts.iter_mut().find(|(number, _)| *number == part.part) {
+                Some((_, durations)) => durations.push(part.elapsed),
+                None => parts.push((part.part, vec![part.elapsed])),
+            }
+        }
+    }
+
+    let mut statistics = Vec::new();
+    if !parse.is_empty() {
+        statistics.push(Statistics::from_durations(day, "parse", &parse));
+    }
+    for (part, durations) in parts {
+        let phase = format!("part {}", part);
+        statistics.push(Statistics::from_durations(day, &phase, &durations));
+    }
+    Ok(statistics)
+}
+
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    results: Vec<Statistics>,
+}
+
+fn baseline_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.toml", name))
+}
+
+/// Stores `statistics` as the baseline called `name`.
+pub fn save(dir: &Path, name: &str, statistics: &[Statistics]) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let baseline = Baseline {
+        results: statistics.to_vec(),
+    };
+    fs::write(baseline_path(dir, name), toml::to_string(&baseline)?)?;
+    Ok(())
+}
+
+/// Loads the baseline called `name`.
+pub fn load(dir: &Path, name: &str) -> Result<Vec<Statistics>> {
+    let path = baseline_path(dir, name);
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("No baseline called {} in {}", name, dir.display()))?;
+    let baseline: Baseline = toml::from_str(&content)
+        .with_context(|| format!("Invalid baseline in {}", path.display()))?;
+    Ok(baseline.results)
+}
+
+/// Replaces the days in `statistics` in the [`LAST`] baseline, keeping the
+/// results of days that weren't benchmarked this time.
+pub fn record_last(dir: &Path, statistics: &[Statistics]) -> Result<()> {
+    let mut results: Vec<Statistics> = load(dir, LAST)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|old| !statistics.iter().any(|new| new.day == old.day))
+        .chain(statistics.iter().cloned())
+        .collect();
+    results.sort_by_key(|statistics| statistics.day);
+    save(dir, LAST, &results)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Faster,
+    Slower,
+    Unchanged,
+}
+
+/// How one phase's timing moved against the baseline.
+#[derive(Debug)]
+pub struct Comparison {
+    pub baseline: Statistics,
+    pub current: Statistics,
+    /// Relative change of the mean, so `0.1` is 10% slower.
+    pub ratio: f64,
+    pub change: Change,
+}
+
+impl Comparison {
+    pub fn new(baseline: Statistics, current: Statistics) -> Self {
+        // Means are in nanoseconds, so measuring against at least one keeps a
+        // zero baseline from making the ratio infinite.
+        let ratio = (current.mean - baseline.mean) / baseline.mean.max(1.0);
+        let standard_error = (baseline.std_dev.powi(2) / baseline.samples as f64
+            + current.std_dev.powi(2) / current.samples as f64)
+            .sqrt();
+        let difference = current.mean - baseline.mean;
+        let significant = if standard_error > 0.0 {
+            (difference / standard_error).abs() > SIGNIFICANT_T
+        } else {
+            difference != 0.0
+        };
+        let change = match (significant, difference > 0.0) {
+            (false, _) => Change::Unchanged,
+            (true, true) => Change::Slower,
+            (true, false) => Change::Faster,
+        };
+        Self {
+            baseline,
+            current,
+            ratio,
+            change,
+        }
+    }
+
+    /// True for a meaningful slowdown of more than `threshold`, given as a
+    /// fraction.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change == Change::Slower && self.ratio > threshold
+    }
+}
+
+/// Pairs up the phases measured in both runs.
+pub fn compare(baseline: &[Statistics], current: &[Statistics]) -> Vec<Comparison> {
+    current
+        .iter()
+        .filter_map(|current| {
+            baseline
+                .iter()
+                .find(|baseline| baseline.day == current.day && baseline.phase == current.phase)
+                .map(|baseline| Comparison::new(baseline.clone(), current.clone()))
+        })
+        .collect()
+}
+
+/// Fails if any comparison is a regression past `threshold`.
+pub fn check_regressions(comparisons: &[Comparison], threshold: f64) -> Result<()> {
+    let regressions: Vec<String> = comparisons
+        .iter()
+        .filter(|comparison| comparison.is_regression(threshold))
+        .map(|comparison| {
+            format!(
+                "day {} {}",
+                comparison.current.day, comparison.current.phase
+            )
+        })
+        .collect();
+    if regressions.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::msg(format!(
+            "Slower than the baseline by more than {:.0}%: {}",
+            threshold * 100.0,
+            regressions.join(", ")
+        )))
+    }
+}
+
+fn nanos(value: f64) -> Duration {
+    Duration::from_nanos(value.max(0.0) as u64)
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {} {}: {:?} ± {:?} ({} samples)",
+            self.day,
+            self.phase,
+            nanos(self.mean),
+            nanos(self.std_dev),
+            self.samples
+        )
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let change = match self.change {
+            Change::Faster => "faster",
+            Change::Slower => "slower",
+            Change::Unchanged => "no change",
+        };
+        write!(
+            f,
+            "Day {} {}: {:?} vs {:?}, {:+.1}% ({})",
+            self.current.day,
+            self.current.phase,
+            nanos(self.current.mean),
+            nanos(self.baseline.mean),
+            self.ratio * 100.0,
+            change
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn statistics(mean: f64, std_dev: f64) -> Statistics {
+        Statistics {
+            day: 4,
+            phase: "part 1".to_string(),
+            samples: 30,
+            mean,
+            std_dev,
+        }
+    }
+
+    #[test]
+    fn test_statistics_from_durations() {
+        let durations = [10, 20, 30].map(Duration::from_nanos);
+
+        let result = Statistics::from_durations(1, "parse", &durations);
+
+        assert_eq!(result.mean, 20.0);
+        assert_eq!(result.std_dev, 10.0);
+        assert_eq!(result.samples, 3);
+    }
+
+    #[test]
+    fn test_benchmark_measures_parse_and_solved_parts() {
+        let entry = runner::find(6).unwrap();
+
+        let result = benchmark(6, entry, "mjqjpqmgbljsphdztnvjfqwrcgsmlb", 3).unwrap();
+
+        let phases: Vec<_> = result.iter().map(|s| s.phase.as_str()).collect();
+        assert_eq!(phases, vec!["parse", "part 1"]);
+        assert!(result.iter().all(|s| s.samples == 3));
+    }
+
+    #[test]
+    fn test_benchmark_fails_on_invalid_input() {
+        let result = benchmark(4, runner::find(4).unwrap(), "1-2", 3);
+
+        assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_benchmark_external_solver_has_no_parse_phase() {
+        let external = crate::external::External {
+            name: "shell".to_string(),
+            command: vec!["sh".to_string(), "-c".to_string(), "echo 1".to_string()],
+            input: Default::default(),
+            days: Vec::new(),
+        };
+
+        let result = benchmark(6, &external, "abcd", 2).unwrap();
+
+        let phases: Vec<_> = result.iter().map(|s| s.phase.as_str()).collect();
+        assert_eq!(phases, vec!["part 1", "part 2"]);
+    }
+
+    #[test]
+    fn test_saves_and_loads_baseline() {
+        let dir = tempdir().unwrap();
+        let results = vec![statistics(100.0, 5.0)];
+
+        save(dir.path(), "main", &results).unwrap();
+
+        assert_eq!(load(dir.path(), "main").unwrap(), results);
+        assert!(load(dir.path(), "other").is_err());
+    }
+
+    #[test]
+    fn test_last_keeps_days_not_benchmarked_again() {
+        let dir = tempdir().unwrap();
+        let day_6 = Statistics {
+            day: 6,
+            ..statistics(50.0, 1.0)
+        };
+        record_last(dir.path(), &[statistics(100.0, 5.0), day_6.clone()]).unwrap();
+
+        record_last(dir.path(), &[statistics(80.0, 5.0)]).unwrap();
+
+        assert_eq!(
+            load(dir.path(), LAST).unwrap(),
+            vec![statistics(80.0, 5.0), day_6]
+        );
+    }
+
+    mod comparison {
+        use super::*;
+
+        #[test]
+        fn test_noise_is_unchanged() {
+            let comparison = Comparison::new(statistics(100.0, 20.0), statistics(105.0, 20.0));
+
+            assert_eq!(comparison.change, Change::Unchanged);
+            assert!(!comparison.is_regression(0.01));
+        }
+
+        #[test]
+        fn test_zero_baseline_gives_finite_ratio() {
+            let unchanged = Comparison::new(statistics(0.0, 0.0), statistics(0.0, 0.0));
+            let slower = Comparison::new(statistics(0.0, 0.0), statistics(50.0, 0.0));
+
+            assert_eq!(unchanged.ratio, 0.0);
+            assert_eq!(unchanged.change, Change::Unchanged);
+            assert_eq!(slower.ratio, 50.0);
+            assert!(slower.is_regression(0.1));
+        }
+
+        #[test]
+        fn test_detects_slowdown() {
+            let comparison = Comparison::new(statistics(100.0, 5.0), statistics(150.0, 5.0));
+
+            assert_eq!(comparison.change, Change::Slower);
+            assert!(comparison.is_regression(0.1));
+            assert!(!comparison.is_regression(0.6));
+        }
+
+        #[test]
+        fn test_detects_speedup() {
+            let comparison = Comparison::new(statistics(100.0, 5.0), statistics(50.0, 5.0));
+
+            assert_eq!(comparison.change, Change::Faster);
+            assert!(!comparison.is_regression(0.0));
+        }
+
+        #[test]
+        fn test_check_regressions_names_slow_phases() {
+            let comparisons = compare(&[statistics(100.0, 5.0)], &[statistics(150.0, 5.0)]);
+
+            let error = check_regressions(&comparisons, 0.1).unwrap_err();
+
+            assert!(error.to_string().contains("day 4 part 1"));
+        }
+    }
+}
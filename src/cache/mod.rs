@@ -0,0 +1,199 @@
+use super::runner::{DayReport, Entry, PartReport, Status};
+use super::{Answer, NotImplemented};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_DIR: &str = ".aoc/cache";
+
+/// Answers stored on disk, keyed by day, part, a hash of the input and the
+/// solution's version.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+/// What solving a part produced. Failures are never cached.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Cached {
+    Answer(Answer),
+    NotImplemented,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Record {
+    result: Cached,
+}
+
+/// Hashes `input` with 64-bit FNV-1a, which is stable between builds.
+pub fn hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl Cache {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    fn path(&self, entry: &Entry, part: usize, input: &str) -> PathBuf {
+        self.dir.join(format!(
+            "day_{}/part_{}/{}-v{}-{:016x}.toml",
+            entry.day,
+            part,
+            entry.name,
+            entry.version,
+            hash(input)
+        ))
+    }
+
+    fn get(&self, entry: &Entry, part: usize, input: &str) -> Option<Cached> {
+        let content = fs::read_to_string(self.path(entry, part, input)).ok()?;
+        let record: Record = toml::from_str(&content).ok()?;
+        Some(record.result)
+    }
+
+    fn put(&self, entry: &Entry, part: usize, input: &str, result: Cached) -> Result<()> {
+        let path = self.path(entry, part, input);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(&Record { result })?)?;
+        Ok(())
+    }
+
+    /// Builds a report from the cache if every part of the day is cached for
+    /// this input.
+    pub fn lookup(&self, entry: &Entry, input: &str) -> Option<DayReport> {
+        let mut report = DayReport::new(entry.day);
+        for part in 1..=2 {
+            let answer = match self.get(entry, part, input)? {
+                Cached::Answer(answer) => Ok(answer),
+                Cached::NotImplemented => Err(NotImplemented {
+                    day: entry.day,
+                    part: Some(part),
+                }
+                .into()),
+            };
+            report.parts.push(PartReport {
+                part,
+                answer,
+                elapsed: Duration::ZERO,
+                memory: None,
+                cached: true,
+            });
+        }
+        Some(report)
+    }
+
+    /// Stores the answers from a day that ran to completion.
+    pub fn store(&self, entry: &Entry, input: &str, report: &DayReport) -> Result<()> {
+        if !matches!(report.status, Status::Completed) {
+            return Ok(());
+        }
+        for part in &report.parts {
+            let result = match &part.answer {
+                Ok(answer) => Cached::Answer(answer.clone()),
+                Err(error) if error.is::<NotImplemented>() => Cached::NotImplemented,
+                Err(_) => continue,
+            };
+            self.put(entry, part.part, input, result)?;
+        }
+        Ok(())
+    }
+
+    /// Removes every cached answer.
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{self, find};
+    use tempfile::tempdir;
+
+    const INPUT: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
+    #[test]
+    fn test_hash_is_stable() {
+        assert_eq!(hash(""), 0xcbf29ce484222325);
+        assert_eq!(hash("a"), 0xaf63dc4c8601ec8c);
+        assert_ne!(hash("ab"), hash("ba"));
+    }
+
+    #[test]
+    fn test_misses_until_stored() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let entry = find(6).unwrap();
+
+        assert!(cache.lookup(entry, INPUT).is_none());
+
+        cache
+            .store(entry, INPUT, &runner::run_input(entry, INPUT))
+            .unwrap();
+        let report = cache.lookup(entry, INPUT).unwrap();
+
+        assert_eq!(report.parts[0].answer.as_ref().unwrap(), &Answer::Number(7));
+        assert!(report.parts[0].cached);
+        assert!(report.parts[1]
+            .answer
+            .as_ref()
+            .unwrap_err()
+            .is::<NotImplemented>());
+        assert!(report.succeeded());
+    }
+
+    #[test]
+    fn test_misses_for_different_input() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let entry = find(6).unwrap();
+        cache
+            .store(entry, INPUT, &runner::run_input(entry, INPUT))
+            .unwrap();
+
+        assert!(cache
+            .lookup(entry, "bvwbjplbgvbhsrlpgdmjqwftvncz")
+            .is_none());
+    }
+
+    #[test]
+    fn test_does_not_store_failed_parts() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(dir.path());
+        let entry = find(1).unwrap();
+        let input = "10\n\n20";
+
+        cache
+            .store(entry, input, &runner::run_input(entry, input))
+            .unwrap();
+
+        assert!(cache.lookup(entry, input).is_none());
+    }
+
+    #[test]
+    fn test_clear_removes_answers() {
+        let dir = tempdir().unwrap();
+        let cache = Cache::new(&dir.path().join("cache"));
+        let entry = find(6).unwrap();
+        cache
+            .store(entry, INPUT, &runner::run_input(entry, INPUT))
+            .unwrap();
+
+        cache.clear().unwrap();
+
+        assert!(cache.lookup(entry, INPUT).is_none());
+        assert!(cache.clear().is_ok());
+    }
+}
@@ -0,0 +1,277 @@
+use super::expected::{self, Expected};
+use super::files::FileReader;
+use super::runner::{self, DayReport, Status};
+use super::NotImplemented;
+use std::fmt;
+use std::time::Duration;
+
+/// Days in the calendar.
+pub const DAYS: usize = 25;
+/// Days shown on each row of the grid.
+const COLUMNS: usize = 5;
+/// Width of a cell, not counting its borders.
+const CELL_WIDTH: usize = 14;
+
+/// How far along one part of a day is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progress {
+    NotImplemented,
+    /// Solved, but there is no stored answer to check it against.
+    Unverified,
+    /// Solved, matching the stored answer.
+    Verified,
+    /// A wrong answer, or an error instead of one.
+    Wrong,
+}
+
+/// A day's square in the calendar.
+#[derive(Debug, PartialEq)]
+pub struct Day {
+    pub day: usize,
+    /// False for days with no implementation registered.
+    pub registered: bool,
+    pub parts: [Progress; 2],
+    /// The fastest time any implementation parsed the input and solved it.
+    pub best: Option<Duration>,
+}
+
+pub struct Calendar {
+    pub days: Vec<Day>,
+}
+
+/// Runs every implementation of every day `runs` times on its puzzle input
+/// and checks the answers stored next to it.
+pub fn calendar(file_reader: &dyn FileReader, runs: usize) -> Calendar {
+    Calendar {
+        days: (1..=DAYS)
+            .map(|day| status(file_reader, day, runs))
+            .collect(),
+    }
+}
+
+/// Works out how far along `day` is.
+pub fn status(file_reader: &dyn FileReader, day: usize, runs: usize) -> Day {
+    let Some(entry) = runner::find(day) else {
+        return Day {
+            day,
+            registered: false,
+            parts: [Progress::NotImplemented; 2],
+            best: None,
+        };
+    };
+    let path = entry.input_path();
+    let Ok(input) = file_reader.read_to_string(&path) else {
+        return Day {
+            day,
+            registered: true,
+            parts: [Progress::Wrong; 2],
+            best: None,
+        };
+    };
+    // Unreadable answers count as missing; `doctor` reports them.
+    let expected = expected::read(file_reader, &path).ok().flatten();
+
+    let report = runner::run_input(entry, &input);
+    let parts = [1, 2].map(|part| progress(&report, part, expected.as_ref()));
+    let best = runner::implementations(day)
+        .into_iter()
+        .flat_map(|entry| (0..runs).map(|_| runner::run_input(entry, &input)))
+        .filter_map(|report| runtime(&report))
+        .min();
+    Day {
+        day,
+        registered: true,
+        parts,
+        best,
+    }
+}
+
+fn progress(report: &DayReport, part: usize, expected: Option<&Expected>) -> Progress {
+    let Some(part_report) = report.parts.iter().find(|report| report.part == part) else {
+        return Progress::Wrong;
+    };
+    match (
+        &part_report.answer,
+        expected.and_then(|expected| expected.get(&part)),
+    ) {
+        (Err(error), _) if error.is::<NotImplemented>() => Progress::NotImplemented,
+        (Err(_), _) => Progress::Wrong,
+        (Ok(answer), Some(expected)) if answer == expected => Progress::Verified,
+        (Ok(_), Some(_)) => Progress::Wrong,
+        (Ok(_), None) => Progress::Unverified,
+    }
+}
+
+/// Time spent parsing and solving the implemented parts, for runs that
+/// completed without errors.
+fn runtime(report: &DayReport) -> Option<Duration> {
+    if !matches!(report.status, Status::Completed) {
+        return None;
+    }
+    let mut total = report.parse?;
+    for part in &report.parts {
+        match &part.answer {
+            Ok(_) => total += part.elapsed,
+            Err(error) if error.is::<NotImplemented>() => {}
+            Err(_) => return None,
+        }
+    }
+    Some(total)
+}
+
+impl Progress {
+    fn symbol(self) -> char {
+        match self {
+            Progress::NotImplemented => '.',
+            Progress::Unverified => '+',
+            Progress::Verified => '*',
+            Progress::Wrong => 'x',
+        }
+    }
+}
+
+impl Calendar {
+    /// How many parts have an answer that matches the stored one.
+    pub fn stars(&self) -> usize {
+        self.days
+            .iter()
+            .flat_map(|day| day.parts)
+            .filter(|part| *part == Progress::Verified)
+            .count()
+    }
+}
+
+impl fmt::Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let border = format!(
+            "+{}",
+            format!("{}+", "-".repeat(CELL_WIDTH)).repeat(COLUMNS)
+        );
+        writeln!(f, "{}", border)?;
+        for row in self.days.chunks(COLUMNS) {
+            let mut title = String::from("|");
+            let mut time = String::from("|");
+            for day in row {
+                let parts: String = if day.registered {
+                    day.parts.iter().map(|part| part.symbol()).collect()
+                } else {
+                    String::new()
+                };
+                let best = day
+                    .best
+                    .map_or(String::new(), |best| format!("{:.1?}", best));
+                title.push_str(&format!(" Day {:<2} {:>5} |", day.day, parts));
+                time.push_str(&format!(" {:>w$} |", best, w = CELL_WIDTH - 2));
+            }
+            writeln!(f, "{}", title)?;
+            writeln!(f, "{}", time)?;
+            writeln!(f, "{}", border)?;
+        }
+        writeln!(
+            f,
+            "* verified  + no stored answer  x wrong or failing  . not implemented"
+        )?;
+        writeln!(f, "{} of {} stars", self.stars(), DAYS * 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::InMemoryReader;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn reader(files: &[(&str, &str)]) -> InMemoryReader {
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+            .collect();
+        InMemoryReader::from(files)
+    }
+
+    const DAY_4: &str = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+    #[test]
+    fn test_verifies_parts_against_stored_answers() {
+        let reader = reader(&[
+            ("assets/day_4/input.txt", DAY_4),
+            ("assets/day_4/input.answers.toml", "part_1 = 2\npart_2 = 4"),
+        ]);
+
+        let result = status(&reader, 4, 1);
+
+        assert_eq!(result.parts, [Progress::Verified; 2]);
+        assert!(result.best.is_some());
+    }
+
+    #[test]
+    fn test_marks_wrong_and_unverified_parts() {
+        let reader = reader(&[
+            ("assets/day_4/input.txt", DAY_4),
+            ("assets/day_4/input.answers.toml", "part_1 = 3"),
+        ]);
+
+        let result = status(&reader, 4, 1);
+
+        assert_eq!(result.parts, [Progress::Wrong, Progress::Unverified]);
+    }
+
+    #[test]
+    fn test_marks_parts_not_implemented() {
+        let reader = reader(&[("assets/day_6/input.txt", "mjqjpqmgbljsphdztnvjfqwrcgsmlb")]);
+
+        let result = status(&reader, 6, 1);
+
+        assert_eq!(
+            result.parts,
+            [Progress::Unverified, Progress::NotImplemented]
+        );
+    }
+
+    #[test]
+    fn test_marks_failing_days() {
+        let reader = reader(&[("assets/day_4/input.txt", "2-4;6-8")]);
+
+        let missing = status(&InMemoryReader::from(HashMap::new()), 4, 1);
+        let invalid = status(&reader, 4, 1);
+
+        assert_eq!(missing.parts, [Progress::Wrong; 2]);
+        assert_eq!((invalid.parts, invalid.best), ([Progress::Wrong; 2], None));
+    }
+
+    #[test]
+    fn test_leaves_unregistered_days_blank() {
+        let result = status(&reader(&[]), 25, 1);
+
+        assert!(!result.registered);
+        assert_eq!(result.best, None);
+    }
+
+    #[test]
+    fn test_renders_grid_of_days() {
+        let mut days: Vec<Day> = (1..=DAYS)
+            .map(|day| Day {
+                day,
+                registered: false,
+                parts: [Progress::NotImplemented; 2],
+                best: None,
+            })
+            .collect();
+        days[3] = Day {
+            day: 4,
+            registered: true,
+            parts: [Progress::Verified, Progress::Unverified],
+            best: Some(Duration::from_micros(1500)),
+        };
+
+        let result = Calendar { days }.to_string();
+        let lines: Vec<&str> = result.lines().collect();
+
+        assert_eq!(lines.len(), 5 * 3 + 3);
+        assert!(lines[1].contains("| Day 4     *+ |"));
+        assert!(lines[2].ends_with("|        1.5ms |              |"));
+        assert!(lines[13].contains("| Day 25       |"));
+        assert_eq!(lines[17], "1 of 50 stars");
+    }
+}
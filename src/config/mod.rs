@@ -0,0 +1,172 @@
+use super::external::External;
+use anyhow::{Context, Error, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub const DEFAULT_PATH: &str = "aoc.toml";
+
+/// Settings read from `aoc.toml`. Every setting is optional.
+///
+/// ```toml
+/// timeout = 10.0
+///
+/// [days.6]
+/// timeout = 2.5
+///
+/// [[solvers]]
+/// name = "python"
+/// command = ["python3", "solutions/main.py"]
+/// ```
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Seconds any day may run before it is abandoned.
+    pub timeout: Option<f64>,
+    #[serde(default)]
+    pub days: HashMap<String, DayConfig>,
+    /// External programs to check against the built-in implementations.
+    #[serde(default)]
+    pub solvers: Vec<External>,
+}
+
+/// Settings that only apply to one day, overriding the top level ones.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DayConfig {
+    pub timeout: Option<f64>,
+}
+
+impl Config {
+    /// Loads the config at `path`, falling back to the defaults if there is
+    /// no such file.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content).with_context(|| format!("Invalid config in {}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)?;
+        if let Some(seconds) = config.timeout {
+            timeout(seconds).context("Invalid timeout")?;
+        }
+        for (day, settings) in &config.days {
+            if let Some(seconds) = settings.timeout {
+                timeout(seconds).with_context(|| format!("Invalid timeout for day {}", day))?;
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn day(&self, day: usize) -> Option<&DayConfig> {
+        self.days.get(&day.to_string())
+    }
+
+    /// The external solvers that solve `day`.
+    pub fn solvers(&self, day: usize) -> Vec<&External> {
+        self.solvers
+            .iter()
+            .filter(|solver| solver.solves(day))
+            .collect()
+    }
+
+    /// The time limit for `day`, if one is configured.
+    pub fn timeout(&self, day: usize) -> Option<Duration> {
+        self.day(day)
+            .and_then(|day| day.timeout)
+            .or(self.timeout)
+            .and_then(|seconds| timeout(seconds).ok())
+    }
+}
+
+/// Turns a number of seconds into a time limit, which must be positive.
+pub fn timeout(seconds: f64) -> Result<Duration> {
+    if !(seconds.is_finite() && seconds > 0.0) {
+        return Err(Error::msg(format!(
+            "{} is not a positive number of seconds",
+            seconds
+        )));
+    }
+    Ok(Duration::try_from_secs_f64(seconds)?)
+}
+
+/// Parses a time limit given in seconds on the command line.
+pub fn parse_timeout(seconds: &str) -> Result<Duration> {
+    timeout(seconds.parse()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::external::InputMode;
+
+    #[test]
+    fn test_defaults_to_no_timeout() {
+        let config = Config::parse("").unwrap();
+
+        assert_eq!(config.timeout(1), None);
+    }
+
+    #[test]
+    fn test_day_timeout_overrides_global_timeout() {
+        let config = Config::parse("timeout = 10\n[days.6]\ntimeout = 0.5\n").unwrap();
+
+        assert_eq!(config.timeout(1), Some(Duration::from_secs(10)));
+        assert_eq!(config.timeout(6), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_rejects_timeouts_that_are_not_positive() {
+        for content in [
+            "timeout = -2\n",
+            "timeout = 0\n",
+            "timeout = nan\n",
+            "[days.6]\ntimeout = inf\n",
+        ] {
+            assert!(Config::parse(content).is_err(), "{}", content);
+        }
+        assert_eq!(
+            format!("{:#}", Config::parse("timeout = -2\n").unwrap_err()),
+            "Invalid timeout: -2 is not a positive number of seconds"
+        );
+    }
+
+    #[test]
+    fn test_parses_timeout_argument() {
+        assert_eq!(parse_timeout("2.5").unwrap(), Duration::from_millis(2500));
+        assert!(parse_timeout("-1").is_err());
+        assert!(parse_timeout("x").is_err());
+    }
+
+    #[test]
+    fn test_reads_external_solvers() {
+        let config = Config::parse(
+            "[[solvers]]\nname = \"awk\"\ncommand = [\"awk\", \"-f\", \"day.awk\"]\ninput = \"path\"\ndays = [6]\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.solvers[0].command, vec!["awk", "-f", "day.awk"]);
+        assert_eq!(config.solvers[0].input, InputMode::Path);
+        assert_eq!(config.solvers(6).len(), 1);
+        assert!(config.solvers(5).is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unknown_settings() {
+        let result = Config::parse("timeut = 10\n");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_falls_back_to_defaults_without_file() {
+        let config = Config::load(Path::new("does-not-exist.toml")).unwrap();
+
+        assert_eq!(config, Config::default());
+    }
+}
@@ -0,0 +1,121 @@
+use anyhow::Result;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+/// A panic caught while running part of a day.
+#[derive(Debug)]
+pub struct Crash {
+    pub day: usize,
+    /// The part being solved, or `None` if the panic happened while parsing.
+    pub part: Option<usize>,
+    pub message: String,
+    pub backtrace: String,
+}
+
+impl fmt::Display for Crash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.part {
+            Some(part) => write!(
+                f,
+                "Day {} part {} panicked: {}",
+                self.day, part, self.message
+            ),
+            None => write!(
+                f,
+                "Day {} panicked while parsing: {}",
+                self.day, self.message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Crash {}
+
+thread_local! {
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static CAUGHT: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, turning a panic into a [`Crash`] error instead of unwinding.
+pub fn catch<T>(day: usize, part: Option<usize>, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    install_hook();
+    let was_catching = CATCHING.with(|catching| catching.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(was_catching));
+
+    result.unwrap_or_else(|_| {
+        let (message, backtrace) = CAUGHT
+            .with(|caught| caught.borrow_mut().take())
+            .unwrap_or_default();
+        Err(Crash {
+            day,
+            part,
+            message,
+            backtrace,
+        }
+        .into())
+    })
+}
+
+/// Records panics raised inside [`catch`] instead of printing them, and leaves
+/// every other panic to the default hook.
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(|catching| catching.get()) {
+                return default_hook(info);
+            }
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Box<dyn Any>".to_string());
+            let message = match info.location() {
+                Some(location) => format!("{} at {}", message, location),
+                None => message,
+            };
+            let backtrace = Backtrace::force_capture().to_string();
+            CAUGHT.with(|caught| *caught.borrow_mut() = Some((message, backtrace)));
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_returns_result_when_nothing_panics() {
+        let result = catch(1, Some(1), || Ok(5));
+
+        assert_eq!(result.unwrap(), 5);
+    }
+
+    #[test]
+    fn test_turns_panic_into_crash() {
+        let error = catch::<()>(1, Some(2), || panic!("Not enough elves")).unwrap_err();
+
+        let crash = error.downcast_ref::<Crash>().unwrap();
+        assert_eq!(crash.day, 1);
+        assert_eq!(crash.part, Some(2));
+        assert!(crash
+            .message
+            .starts_with("Not enough elves at src/crash/mod.rs"));
+        assert!(!crash.backtrace.is_empty());
+    }
+
+    #[test]
+    fn test_describes_parse_panics() {
+        let error = catch::<()>(5, None, || panic!("Bad stacks")).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("Day 5 panicked while parsing: Bad stacks"));
+    }
+}
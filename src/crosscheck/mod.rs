@@ -0,0 +1,214 @@
+use super::expected;
+use super::files::FileReader;
+use super::generate;
+use super::runner::{self, Solver};
+use super::{Answer, NotImplemented};
+use anyhow::Result;
+use std::fmt;
+
+/// An input to check, with a label saying where it came from.
+pub struct Input {
+    pub label: String,
+    pub text: String,
+}
+
+/// A part that implementations answered differently.
+pub struct Disagreement {
+    pub day: usize,
+    pub part: usize,
+    pub input: String,
+    /// Each implementation's answer, or why it failed.
+    pub outcomes: Vec<(String, Result<Answer, String>)>,
+}
+
+/// The puzzle input and examples that can be read for the day, followed by
+/// `generated` inputs made from consecutive seeds.
+pub fn inputs(
+    day: usize,
+    file_reader: &dyn FileReader,
+    seed: u64,
+    generated: usize,
+    size: usize,
+) -> Result<Vec<Input>> {
+    let entry = runner::find(day).ok_or(NotImplemented { day, part: None })?;
+    let mut inputs = Vec::new();
+    let mut paths = vec![entry.input_path()];
+    paths.extend(expected::examples(day)?);
+    for path in paths {
+        if let Ok(text) = file_reader.read_to_string(&path) {
+            inputs.push(Input {
+                label: path.display().to_string(),
+                text,
+            });
+        }
+    }
+    for seed in (seed..).take(generated) {
+        match generate::generate(day, seed, size) {
+            Ok(text) => inputs.push(Input {
+                label: format!("input generated with --seed {} --size {}", seed, size),
+                text,
+            }),
+            Err(_) => break,
+        }
+    }
+    Ok(inputs)
+}
+
+/// Runs every implementation of `day` on every input and collects the parts
+/// where they disagree. Parts an implementation doesn't have are left out,
+/// and implementations that all fail agree with each other.
+pub fn crosscheck(
+    day: usize,
+    implementations: &[&dyn Solver],
+    inputs: &[Input],
+) -> Vec<Disagreement> {
+    let mut disagreements = Vec::new();
+    for input in inputs {
+        let reports: Vec<_> = implementations
+            .iter()
+            .map(|solver| (solver.name(), solver.run_input(day, &input.text)))
+            .collect();
+        for part in 1..=2 {
+            let outcomes: Vec<_> = reports
+                .iter()
+                .filter_map(|(name, report)| {
+                    let answer = match report.parts.iter().find(|report| report.part == part) {
+                        Some(report) => match &report.answer {
+                            Ok(answer) => Ok(answer.clone()),
+                            Err(error) if error.is::<NotImplemented>() => return None,
+                            Err(error) => Err(format!("{:#}", error)),
+                        },
+                        None => Err(report.status.to_string()),
+                    };
+                    Some((name.to_string(), answer))
+                })
+                .collect();
+            let agree = outcomes
+                .windows(2)
+                .all(|pair| pair[0].1.as_ref().ok() == pair[1].1.as_ref().ok());
+            if !agree {
+                disagreements.push(Disagreement {
+                    day,
+                    part,
+                    input: input.label.clone(),
+                    outcomes,
+                });
+            }
+        }
+    }
+    disagreements
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Day {} part {} disagrees on {}",
+            self.day, self.part, self.input
+        )?;
+        for (name, answer) in &self.outcomes {
+            match answer {
+                Ok(answer) => writeln!(f, "  {}: {}", name, answer)?,
+                Err(error) => writeln!(f, "  {}: failed: {}", name, error)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::InMemoryReader;
+    use crate::runner::Entry;
+    use crate::Solution;
+
+    /// A day 6 implementation that is always wrong.
+    struct Wrong;
+
+    impl Solution for Wrong {
+        const DAY: usize = 6;
+        const VERSION: u32 = 1;
+        const NAME: &'static str = "wrong";
+        type Parsed = ();
+
+        fn parse(_input: &str) -> Result<Self::Parsed> {
+            Ok(())
+        }
+
+        fn part_1(_parsed: &Self::Parsed) -> Result<Answer> {
+            Ok(Answer::Number(0))
+        }
+    }
+
+    fn day_6() -> Vec<&'static dyn Solver> {
+        runner::implementations(6)
+            .into_iter()
+            .map(|entry| entry as &dyn Solver)
+            .collect()
+    }
+
+    fn input(text: &str) -> Input {
+        Input {
+            label: "test".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_implementations_of_day_6_agree() {
+        let inputs = inputs(6, &InMemoryReader::from("abcd"), 0, 50, 200).unwrap();
+
+        assert!(inputs.len() > 50);
+        assert!(crosscheck(6, &day_6(), &inputs).is_empty());
+    }
+
+    #[test]
+    fn test_reports_disagreeing_part() {
+        let wrong = Entry::of::<Wrong>();
+        let implementations: Vec<&dyn Solver> = vec![runner::find(6).unwrap(), &wrong];
+
+        let result = crosscheck(6, &implementations, &[input("abcd")]);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!((result[0].day, result[0].part), (6, 1));
+        let report = result[0].to_string();
+        assert!(report.contains("unique: 4"));
+        assert!(report.contains("wrong: 0"));
+    }
+
+    #[test]
+    fn test_failures_agree_with_each_other() {
+        let result = crosscheck(6, &day_6(), &[input("aaaa")]);
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_failure_disagrees_with_answer() {
+        let wrong = Entry::of::<Wrong>();
+        let implementations: Vec<&dyn Solver> = vec![runner::find(6).unwrap(), &wrong];
+
+        let result = crosscheck(6, &implementations, &[input("aaaa")]);
+
+        assert_eq!(result.len(), 1);
+        assert!(result[0].to_string().contains("unique: failed"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_checks_external_solvers() {
+        let external = crate::external::External {
+            name: "shell".to_string(),
+            command: vec!["sh".to_string(), "-c".to_string(), "echo 7".to_string()],
+            input: Default::default(),
+            days: Vec::new(),
+        };
+        let implementations: Vec<&dyn Solver> = vec![runner::find(6).unwrap(), &external];
+
+        let result = crosscheck(6, &implementations, &[input("abcd")]);
+
+        assert_eq!(result.len(), 1);
+        assert!(result[0].to_string().contains("shell: 7"));
+    }
+}
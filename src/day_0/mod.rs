@@ -5,6 +5,7 @@ pub struct Day0;
 impl Day<()> for Day0 {
     fn run(&self) -> Result<()> {
         println!("Day 0!");
+        println!("Run `doctor` to check that every day is ready to run.");
         Ok(())
     }
 }
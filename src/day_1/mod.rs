@@ -1,6 +1,8 @@
-use super::files::FileReader;
-use super::Day;
-use anyhow::Result;
+use super::files::{FileReader, ReadsInput};
+use super::generate::Rng;
+use super::parse;
+use super::{Answer, Day, Solution};
+use anyhow::{Context, Error, Result};
 use std::path::Path;
 
 pub struct Day1 {
@@ -17,42 +19,102 @@ impl Default for Day1 {
     }
 }
 
+impl ReadsInput for Day1 {
+    fn with_reader(file_reader: Box<dyn FileReader>) -> Self {
+        Self {
+            file_reader,
+            ..Self::default()
+        }
+    }
+}
+
 impl Day<usize> for Day1 {
     fn run(&self) -> Result<usize> {
-        let input = self.file_reader.read_file(self.file_path)?;
-        let total_calories = process_input(input);
-        let top_three = top_three(&total_calories);
-        println!("Top three: {:?}", top_three);
-        Ok(top_three.iter().sum())
+        let input = self.file_reader.read_to_string(self.file_path)?;
+        top_three_total(&input)
     }
 }
 
-fn process_input(input: Vec<String>) -> Vec<usize> {
-    let mut total_calories = vec![];
-    let mut current_calories = 0;
-    for line in input {
-        if line == "" {
-            total_calories.push(current_calories);
-            current_calories = 0;
-        } else {
-            current_calories += line.parse::<usize>().unwrap();
-        }
+impl Solution for Day1 {
+    const DAY: usize = 1;
+    const VERSION: u32 = 1;
+    const TITLE: &'static str = "Calorie Counting";
+    /// The total calories carried by each elf.
+    type Parsed = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        process_input(input)
+    }
+
+    fn part_2(total_calories: &Self::Parsed) -> Result<Answer> {
+        Ok(sum_top_three(total_calories)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_elves(rng, size))
+    }
+}
+
+/// Sums the calories carried by the three elves carrying the most.
+pub fn top_three_total(input: &str) -> Result<usize> {
+    sum_top_three(&Day1::parse(input)?)
+}
+
+fn sum_top_three(total_calories: &[usize]) -> Result<usize> {
+    if total_calories.len() < 3 {
+        return Err(Error::msg(format!(
+            "Need at least three elves, found {}",
+            total_calories.len()
+        )));
     }
-    total_calories.push(current_calories);
-    total_calories
+    top_three(total_calories)
+        .iter()
+        .try_fold(0usize, |sum, calories| sum.checked_add(*calories))
+        .ok_or_else(|| Error::msg("Total calories are too large"))
+}
+
+fn process_input(input: &str) -> Result<Vec<usize>> {
+    parse::blocks(input)
+        .iter()
+        .map(|elf| {
+            elf.integers::<usize>()
+                .context("Invalid calories")?
+                .into_iter()
+                .try_fold(0usize, |sum, calories| sum.checked_add(calories))
+                .ok_or_else(|| {
+                    Error::msg(format!(
+                        "Total calories are too large for the elf at line {}",
+                        elf.first_line
+                    ))
+                })
+        })
+        .collect()
 }
 
-fn top_three(numbers: &Vec<usize>) -> Vec<usize> {
-    let mut numbers = numbers.clone();
+fn top_three(numbers: &[usize]) -> Vec<usize> {
+    let mut numbers = numbers.to_vec();
     numbers.sort();
     numbers.reverse();
     numbers[..3].to_vec()
 }
 
+/// Writes `size` elves, each carrying a few snacks.
+fn generate_elves(rng: &mut Rng, size: usize) -> String {
+    let elves: Vec<String> = (0..size.max(3))
+        .map(|_| {
+            let snacks: Vec<String> = (0..rng.between(1, 15))
+                .map(|_| rng.between(1000, 70000).to_string())
+                .collect();
+            snacks.join("\n")
+        })
+        .collect();
+    elves.join("\n\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::files::tests::MockFileReader;
+    use crate::files::InMemoryReader;
 
     #[test]
     fn test_returns_sum_of_top_3_calories() {
@@ -67,7 +129,7 @@ mod tests {
             "23".to_string(),
         ];
         let day = Day1 {
-            file_reader: Box::new(MockFileReader { lines }),
+            file_reader: Box::new(InMemoryReader::from(lines)),
             file_path: Path::new("some-file.txt"),
         };
 
@@ -76,20 +138,45 @@ mod tests {
         assert_eq!(result, 83);
     }
 
+    #[test]
+    fn test_top_three_total_from_text() {
+        let result = top_three_total("10\n20\n\n10\n\n30\n\n23").unwrap();
+
+        assert_eq!(result, 83);
+    }
+
     #[test]
     fn test_process_input() {
-        let input = vec![
-            "10".to_string(),
-            "20".to_string(),
-            "".to_string(),
-            "10".to_string(),
-        ];
+        let input = "10\n20\n\n10";
 
-        let result = process_input(input);
+        let result = process_input(input).unwrap();
 
         assert_eq!(result, vec![30, 10]);
     }
 
+    #[test]
+    fn test_returns_error_for_fewer_than_three_elves() {
+        assert!(top_three_total("10\n\n20").is_err());
+    }
+
+    #[test]
+    fn test_returns_error_when_calories_overflow() {
+        let input = format!("{}\n1", usize::MAX);
+
+        assert!(process_input(&input).is_err());
+    }
+
+    #[test]
+    fn test_returns_error_for_malformed_calories() {
+        for calories in ["12abc", "1.5", "10 20", "1e3"] {
+            let input = format!("{}\n\n1\n\n2", calories);
+
+            let result = crate::solve(1, 2, &input);
+
+            assert!(result.is_err(), "{}", calories);
+        }
+    }
+
     #[test]
     fn test_returns_top_three() {
         let numbers = vec![1, 2, 3, 222, 4, 5, 388];
@@ -98,4 +185,53 @@ mod tests {
 
         assert_eq!(result, vec![388, 222, 5]);
     }
+
+    #[test]
+    fn test_generates_one_block_per_elf() {
+        let input = generate_elves(&mut Rng::new(1), 25);
+
+        assert_eq!(process_input(&input).unwrap().len(), 25);
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn elves() -> impl Strategy<Value = Vec<Vec<usize>>> {
+            prop::collection::vec(prop::collection::vec(0..100_000usize, 1..5), 3..20)
+        }
+
+        fn render(elves: &[Vec<usize>]) -> String {
+            elves
+                .iter()
+                .map(|calories| {
+                    calories
+                        .iter()
+                        .map(|calories| calories.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        }
+
+        proptest! {
+            #[test]
+            fn test_totals_each_elf(elves in elves()) {
+                let totals: Vec<usize> = elves.iter().map(|calories| calories.iter().sum()).collect();
+
+                prop_assert_eq!(process_input(&render(&elves)).unwrap(), totals);
+            }
+
+            #[test]
+            fn test_top_three_are_the_largest_totals(elves in elves()) {
+                let mut totals: Vec<usize> = elves.iter().map(|calories| calories.iter().sum()).collect();
+                totals.sort_unstable_by(|a, b| b.cmp(a));
+
+                let result = top_three_total(&render(&elves)).unwrap();
+
+                prop_assert_eq!(result, totals[..3].iter().sum::<usize>());
+            }
+        }
+    }
 }
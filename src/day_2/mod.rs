@@ -1,5 +1,6 @@
-use super::files::FileReader;
-use super::Day;
+use super::files::{FileReader, ReadsInput};
+use super::generate::Rng;
+use super::{Answer, Day, Solution};
 use anyhow::{Error, Result};
 use std::path::Path;
 
@@ -17,6 +18,15 @@ impl Default for Day2 {
     }
 }
 
+impl ReadsInput for Day2 {
+    fn with_reader(file_reader: Box<dyn FileReader>) -> Self {
+        Self {
+            file_reader,
+            ..Self::default()
+        }
+    }
+}
+
 enum Signals {
     Rock = 1,
     Paper = 2,
@@ -25,34 +35,66 @@ enum Signals {
 
 impl Day<usize> for Day2 {
     fn run(&self) -> Result<usize> {
-        let input = self.file_reader.read_file(self.file_path)?;
-        let mut score = 0;
-        for game in input {
-            score += get_score(&game);
-        }
+        let input = self.file_reader.read_to_string(self.file_path)?;
+        let score = total_score(&input)?;
         println!("Total score: {}", score);
         Ok(score)
     }
 }
 
-fn get_score(game: &str) -> usize {
-    let (opponent, result) = game.split_once(' ').unwrap();
-    let (opponent, result) = (
-        opponent.chars().next().unwrap(),
-        result.chars().next().unwrap(),
-    );
-    let signal_score = get_required_signal(opponent, result).unwrap();
-    let result_score = get_result_score(result).unwrap();
-    println!(
-        "{} vs {}: ({} + {}) = {}",
-        opponent,
-        result,
-        signal_score,
-        result_score,
-        signal_score + result_score
-    );
-
-    signal_score + result_score
+impl Solution for Day2 {
+    const DAY: usize = 2;
+    const VERSION: u32 = 1;
+    const TITLE: &'static str = "Rock Paper Scissors";
+    /// The opponent's signal and the required result for each round.
+    type Parsed = Vec<(char, char)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        input.lines().map(parse_round).collect()
+    }
+
+    fn part_2(rounds: &Self::Parsed) -> Result<Answer> {
+        Ok(score_rounds(rounds)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_rounds(rng, size))
+    }
+}
+
+/// Scores every round, treating the second column as the required result.
+pub fn total_score(input: &str) -> Result<usize> {
+    score_rounds(&Day2::parse(input)?)
+}
+
+fn score_rounds(rounds: &[(char, char)]) -> Result<usize> {
+    let mut score = 0;
+    for (opponent, result) in rounds {
+        score += score_round(*opponent, *result)?;
+    }
+    Ok(score)
+}
+
+crate::line_pattern! {
+    "{opponent} {result}"
+    /// A line of the strategy guide: the opponent's shape and how the round
+    /// should end.
+    struct Round {
+        opponent: char,
+        result: char,
+    }
+}
+
+fn parse_round(game: &str) -> Result<(char, char)> {
+    let Round { opponent, result } = game.parse()?;
+    Ok((opponent, result))
+}
+
+fn score_round(opponent: char, result: char) -> Result<usize> {
+    let signal_score = get_required_signal(opponent, result)?;
+    let result_score = get_result_score(result)?;
+
+    Ok(signal_score + result_score)
 }
 
 fn get_result_score(me: char) -> Result<usize> {
@@ -81,10 +123,24 @@ fn get_required_signal(opponent: char, result: char) -> Result<usize> {
     Ok(required as usize)
 }
 
+/// Writes `size` rounds of an opponent's signal and a required result.
+fn generate_rounds(rng: &mut Rng, size: usize) -> String {
+    let rounds: Vec<String> = (0..size)
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.pick(&['A', 'B', 'C']),
+                rng.pick(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect();
+    rounds.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::files::tests::MockFileReader;
+    use crate::files::InMemoryReader;
 
     #[test]
     fn test_returns_sum_of_score() {
@@ -94,7 +150,7 @@ mod tests {
             "C Z".to_string(), // 7
         ];
         let day = Day2 {
-            file_reader: Box::new(MockFileReader { lines }),
+            file_reader: Box::new(InMemoryReader::from(lines)),
             file_path: Path::new("some-file.txt"),
         };
 
@@ -103,16 +159,32 @@ mod tests {
         assert_eq!(result, 12);
     }
 
-    mod get_score {
+    mod score_round {
         use super::*;
 
         #[test]
         fn test_returns_sum_of_result_and_signal() {
-            let result = get_score("A Y");
+            let result = score_round('A', 'Y').unwrap();
             assert_eq!(result, 4);
         }
     }
 
+    mod parse_round {
+        use super::*;
+
+        #[test]
+        fn test_returns_signal_and_result() {
+            let result = parse_round("A Y").unwrap();
+            assert_eq!(result, ('A', 'Y'));
+        }
+
+        #[test]
+        fn test_returns_error_for_invalid_round() {
+            let result = parse_round("AY");
+            assert!(result.is_err());
+        }
+    }
+
     mod get_result_score {
         use super::*;
 
@@ -156,4 +228,57 @@ mod tests {
             assert_eq!(result, 3);
         }
     }
+
+    #[test]
+    fn test_generates_valid_rounds() {
+        let input = generate_rounds(&mut Rng::new(1), 25);
+
+        assert_eq!(Day2::parse(&input).unwrap().len(), 25);
+        assert!(total_score(&input).is_ok());
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn round() -> impl Strategy<Value = (char, char)> {
+            (
+                prop::sample::select(vec!['A', 'B', 'C']),
+                prop::sample::select(vec!['X', 'Y', 'Z']),
+            )
+        }
+
+        proptest! {
+            #[test]
+            fn test_required_signal_gives_required_result((opponent, result) in round()) {
+                let opponent_signal = (opponent as usize - 'A' as usize + 1) % 3;
+                let required = get_required_signal(opponent, result).unwrap() % 3;
+
+                // Each signal beats the one before it, wrapping around.
+                let expected = match result {
+                    'X' => (opponent_signal + 2) % 3,
+                    'Y' => opponent_signal,
+                    _ => (opponent_signal + 1) % 3,
+                };
+                prop_assert_eq!(required, expected);
+            }
+
+            #[test]
+            fn test_total_is_sum_of_rounds(rounds in prop::collection::vec(round(), 0..50)) {
+                let input: Vec<String> = rounds
+                    .iter()
+                    .map(|(opponent, result)| format!("{} {}", opponent, result))
+                    .collect();
+
+                let total = total_score(&input.join("\n")).unwrap();
+
+                let scores: Vec<usize> = rounds
+                    .iter()
+                    .map(|(opponent, result)| score_round(*opponent, *result).unwrap())
+                    .collect();
+                prop_assert!(scores.iter().all(|score| (1..=9).contains(score)));
+                prop_assert_eq!(total, scores.iter().sum::<usize>());
+            }
+        }
+    }
 }
@@ -1,7 +1,9 @@
-use super::files::FileReader;
-use super::Day;
+use super::files::{FileReader, ReadsInput};
+use super::generate::Rng;
+use super::{Answer, Day, Solution};
 use anyhow::Error;
 use anyhow::Result;
+use serde::Serialize;
 use std::path::Path;
 
 const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
@@ -20,39 +22,123 @@ impl Default for Day3 {
     }
 }
 
+impl ReadsInput for Day3 {
+    fn with_reader(file_reader: Box<dyn FileReader>) -> Self {
+        Self {
+            file_reader,
+            ..Self::default()
+        }
+    }
+}
+
 impl Day<(usize, usize)> for Day3 {
     fn run(&self) -> Result<(usize, usize)> {
-        let input = self.file_reader.read_file(self.file_path)?;
-        let mut total = 0;
-        let mut id_total = 0;
-        let mut group = Vec::new();
-        for rucksack in input {
-            group.push(rucksack.clone());
-            if group.len() == 3 {
-                id_total += get_identifier_score(&group).unwrap();
-                group.clear();
-            }
-            total += get_compartment_score(&rucksack);
-        }
+        let input = self.file_reader.read_to_string(self.file_path)?;
+        let rucksacks = Day3::parse(&input)?;
+        let total = sum_compartments(&rucksacks)?;
+        let id_total = sum_badges(&rucksacks)?;
         println!("Total: {}", total);
         println!("Sticker total: {}", id_total);
         Ok((total, id_total))
     }
 }
 
-fn get_identifier_score(group: &Vec<String>) -> Result<usize> {
+impl Solution for Day3 {
+    const DAY: usize = 3;
+    const VERSION: u32 = 1;
+    const TITLE: &'static str = "Rucksack Reorganization";
+    /// The items in each rucksack, split into its compartments.
+    type Parsed = Vec<Rucksack>;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        input
+            .lines()
+            .map(|line| match line.chars().find(|c| !ALPHABET.contains(*c)) {
+                Some(c) => Err(Error::msg(format!("Invalid item {:?} in {}", c, line))),
+                None => Ok(Rucksack::new(line)),
+            })
+            .collect()
+    }
+
+    fn part_1(rucksacks: &Self::Parsed) -> Result<Answer> {
+        Ok(sum_compartments(rucksacks)?.into())
+    }
+
+    fn part_2(rucksacks: &Self::Parsed) -> Result<Answer> {
+        Ok(sum_badges(rucksacks)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_rucksacks(rng, size))
+    }
+}
+
+/// A rucksack's items, the first half in one compartment and the second half
+/// in the other.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Rucksack {
+    pub first: String,
+    pub second: String,
+}
+
+impl Rucksack {
+    fn new(items: &str) -> Self {
+        let (first, second) = split(items);
+        Self {
+            first: first.to_string(),
+            second: second.to_string(),
+        }
+    }
+
+    fn contains(&self, item: char) -> bool {
+        self.first.contains(item) || self.second.contains(item)
+    }
+}
+
+/// Sums the priorities of the item found in both compartments of each rucksack.
+pub fn compartment_total(input: &str) -> Result<usize> {
+    sum_compartments(&Day3::parse(input)?)
+}
+
+/// Sums the priorities of the badge shared by each group of three rucksacks.
+pub fn badge_total(input: &str) -> Result<usize> {
+    sum_badges(&Day3::parse(input)?)
+}
+
+fn sum_compartments(rucksacks: &[Rucksack]) -> Result<usize> {
+    let mut total = 0;
+    for rucksack in rucksacks {
+        total += get_compartment_score(rucksack)?;
+    }
+    Ok(total)
+}
+
+fn sum_badges(rucksacks: &[Rucksack]) -> Result<usize> {
+    if !rucksacks.len().is_multiple_of(3) {
+        return Err(Error::msg(format!(
+            "Elves come in groups of three, but there are {} rucksacks",
+            rucksacks.len()
+        )));
+    }
+    let mut id_total = 0;
+    for group in rucksacks.chunks_exact(3) {
+        id_total += get_identifier_score(group)?;
+    }
+    Ok(id_total)
+}
+
+fn get_identifier_score(group: &[Rucksack]) -> Result<usize> {
     for c in ALPHABET.chars() {
         if group[0].contains(c) && group[1].contains(c) && group[2].contains(c) {
-            return Ok(score_for_char(c));
+            return score_for_char(c);
         }
     }
 
     Err(Error::msg("No match found in group"))
 }
 
-fn get_compartment_score(rucksack: &str) -> usize {
-    let (c1, c2) = split(&rucksack);
-    let duplicate_char = find_duplicate_char(c1, c2).unwrap();
+fn get_compartment_score(rucksack: &Rucksack) -> Result<usize> {
+    let duplicate_char = find_duplicate_char(&rucksack.first, &rucksack.second)?;
     score_for_char(duplicate_char)
 }
 
@@ -70,32 +156,65 @@ fn find_duplicate_char(c1: &str, c2: &str) -> Result<char> {
     Err(Error::msg("No duplicate char found"))
 }
 
-fn score_for_char(c: char) -> usize {
-    ALPHABET.find(c).unwrap() + 1
+fn score_for_char(c: char) -> Result<usize> {
+    ALPHABET
+        .find(c)
+        .map(|index| index + 1)
+        .ok_or_else(|| Error::msg(format!("Invalid item: {:?}", c)))
+}
+
+/// Writes `size` rucksacks, rounded up to whole groups of three. Each
+/// rucksack's compartments share exactly one item, and each group shares
+/// exactly one badge.
+fn generate_rucksacks(rng: &mut Rng, size: usize) -> String {
+    let mut rucksacks = Vec::new();
+    for _ in 0..size.div_ceil(3) {
+        let mut letters: Vec<char> = ALPHABET.chars().collect();
+        rng.shuffle(&mut letters);
+        let badge = letters[0];
+        // Each rucksack in the group draws from its own 17 letters, so only
+        // the badge is common to all three.
+        for own in letters[1..].chunks(17) {
+            let (shared, rest) = own.split_first().unwrap();
+            let (left_items, right_items) = rest.split_at(rest.len() / 2);
+            let half = rng.between(2, 16);
+            let mut left = vec![*shared, badge];
+            left.extend((2..half).map(|_| rng.pick(left_items)));
+            let mut right = vec![*shared];
+            right.extend((1..half).map(|_| rng.pick(right_items)));
+            rng.shuffle(&mut left);
+            rng.shuffle(&mut right);
+            rucksacks.push(left.into_iter().chain(right).collect::<String>());
+        }
+    }
+    rucksacks.join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::files::tests::MockFileReader;
+    use crate::files::InMemoryReader;
 
     #[test]
     fn test_returns_sum_of_duplicate_items() {
-        let lines = vec!["abcdbf".to_string(), "ABCDBF".to_string()];
-        let day = Day3 {
-            file_reader: Box::new(MockFileReader { lines }),
-            file_path: Path::new("some-file.txt"),
-        };
-        let result = day.run().unwrap();
+        let result = compartment_total("abcdbf\nABCDBF").unwrap();
 
-        assert_eq!(result.0, 30);
+        assert_eq!(result, 30);
+    }
+
+    #[test]
+    fn test_returns_error_for_incomplete_group() {
+        let result = badge_total("abcdbf\nAbCDbF\nbb\naa");
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("4 rucksacks"));
     }
 
     #[test]
     fn test_returns_sum_of_common_group_items() {
         let lines = vec!["abcdbf".to_string(), "AbCDbF".to_string(), "bb".to_string()];
         let day = Day3 {
-            file_reader: Box::new(MockFileReader { lines }),
+            file_reader: Box::new(InMemoryReader::from(lines)),
             file_path: Path::new("some-file.txt"),
         };
 
@@ -115,9 +234,16 @@ mod tests {
 
     #[test]
     fn test_score_for_char() {
-        assert_eq!(score_for_char('a'), 1);
-        assert_eq!(score_for_char('A'), 27);
-        assert_eq!(score_for_char('Z'), 52);
+        assert_eq!(score_for_char('a').unwrap(), 1);
+        assert_eq!(score_for_char('A').unwrap(), 27);
+        assert_eq!(score_for_char('Z').unwrap(), 52);
+        assert!(score_for_char('!').is_err());
+    }
+
+    #[test]
+    fn test_returns_error_for_items_outside_alphabet() {
+        assert!(compartment_total("aé").is_err());
+        assert!(compartment_total("ab-a").is_err());
     }
 
     mod find_duplicate_character {
@@ -153,4 +279,70 @@ mod tests {
             assert_eq!(result, 'B');
         }
     }
+
+    #[test]
+    fn test_generated_rucksacks_share_one_item_and_one_badge() {
+        let input = generate_rucksacks(&mut Rng::new(1), 10);
+        let rucksacks = Day3::parse(&input).unwrap();
+
+        assert_eq!(rucksacks.len(), 12);
+        for rucksack in &rucksacks {
+            let shared: Vec<char> = ALPHABET
+                .chars()
+                .filter(|c| rucksack.first.contains(*c) && rucksack.second.contains(*c))
+                .collect();
+            assert_eq!(shared.len(), 1, "{:?}", rucksack);
+        }
+        for group in rucksacks.chunks(3) {
+            let badges = ALPHABET
+                .chars()
+                .filter(|c| group.iter().all(|rucksack| rucksack.contains(*c)));
+            assert_eq!(badges.count(), 1);
+        }
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// A rucksack whose compartments share exactly one item, and that item.
+        fn rucksack() -> impl Strategy<Value = (String, char)> {
+            let letters: Vec<char> = ALPHABET.chars().collect();
+            (Just(letters).prop_shuffle(), 0..12usize)
+                .prop_flat_map(|(letters, size)| {
+                    let item = prop::collection::vec(0..25usize, size);
+                    (Just(letters), item.clone(), item, 0..=size, 0..=size)
+                })
+                .prop_map(|(letters, left, right, left_at, right_at)| {
+                    let shared = letters[0];
+                    let mut first: Vec<char> = left.iter().map(|i| letters[1 + i]).collect();
+                    let mut second: Vec<char> = right.iter().map(|i| letters[26 + i]).collect();
+                    first.insert(left_at, shared);
+                    second.insert(right_at, shared);
+                    (first.into_iter().chain(second).collect(), shared)
+                })
+        }
+
+        proptest! {
+            #[test]
+            fn test_scores_the_shared_item((rucksack, shared) in rucksack()) {
+                let result = compartment_total(&rucksack).unwrap();
+
+                prop_assert_eq!(result, ALPHABET.find(shared).unwrap() + 1);
+            }
+
+            #[test]
+            fn test_total_is_sum_of_rucksacks(rucksacks in prop::collection::vec(rucksack(), 0..20)) {
+                let input: Vec<String> = rucksacks.iter().map(|(rucksack, _)| rucksack.clone()).collect();
+
+                let result = compartment_total(&input.join("\n")).unwrap();
+
+                let expected: usize = rucksacks
+                    .iter()
+                    .map(|(_, shared)| ALPHABET.find(*shared).unwrap() + 1)
+                    .sum();
+                prop_assert_eq!(result, expected);
+            }
+        }
+    }
 }
@@ -1,12 +1,26 @@
-use super::files::FileReader;
-use super::Day;
-use anyhow::Result;
+use super::files::{FileReader, ReadsInput};
+use super::generate::Rng;
+use super::{Answer, Day, Solution};
+use anyhow::{Error, Result};
+use serde::Serialize;
 use std::path::Path;
 
-#[derive(PartialEq, Debug)]
-struct Range {
-    min: usize,
-    max: usize,
+crate::line_pattern! {
+    "{min}-{max}"
+    #[derive(PartialEq, Debug, Serialize)]
+    pub struct Range {
+        min: usize,
+        max: usize,
+    }
+}
+
+crate::line_pattern! {
+    "{first},{second}"
+    /// The ranges of one pair, not yet checked.
+    struct Pair {
+        first: Range,
+        second: Range,
+    }
 }
 
 pub struct Day4 {
@@ -23,81 +37,122 @@ impl Default for Day4 {
     }
 }
 
+impl ReadsInput for Day4 {
+    fn with_reader(file_reader: Box<dyn FileReader>) -> Self {
+        Self {
+            file_reader,
+            ..Self::default()
+        }
+    }
+}
+
 impl Day<(usize, usize)> for Day4 {
     fn run(&self) -> Result<(usize, usize)> {
-        let input = self.file_reader.read_file(self.file_path)?;
-        let mut total_overlaps = 0;
-        let mut partials = 0;
-        for line in input {
-            let (p1, p2) = make_pairs(line);
-            if overlaps(&p1, &p2) {
-                total_overlaps += 1;
-            }
-            if partial_overlaps(&p1, &p2) {
-                partials += 1;
-            }
-        }
+        let input = self.file_reader.read_to_string(self.file_path)?;
+        let pairs = Day4::parse(&input)?;
+        let total_overlaps = count_pairs(&pairs, overlaps);
+        let partials = count_pairs(&pairs, partial_overlaps);
         println!("Total overlaps: {}", total_overlaps);
         println!("Partial overlaps: {}", partials);
         Ok((total_overlaps, partials))
     }
 }
 
-fn make_pair(range_string: &str) -> Range {
-    let (min, max) = range_string.split_once('-').unwrap();
-    Range {
-        min: min.parse::<usize>().unwrap(),
-        max: max.parse::<usize>().unwrap(),
+impl Solution for Day4 {
+    const DAY: usize = 4;
+    const VERSION: u32 = 1;
+    const TITLE: &'static str = "Camp Cleanup";
+    /// The section ranges assigned to each pair of elves.
+    type Parsed = Vec<(Range, Range)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        input.lines().map(make_pairs).collect()
+    }
+
+    fn part_1(pairs: &Self::Parsed) -> Result<Answer> {
+        Ok(count_pairs(pairs, overlaps).into())
+    }
+
+    fn part_2(pairs: &Self::Parsed) -> Result<Answer> {
+        Ok(count_pairs(pairs, partial_overlaps).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_pairs(rng, size))
+    }
+}
+
+/// Counts the pairs where one range fully contains the other.
+pub fn count_overlaps(input: &str) -> Result<usize> {
+    Ok(count_pairs(&Day4::parse(input)?, overlaps))
+}
+
+/// Counts the pairs whose ranges share at least one section.
+pub fn count_partial_overlaps(input: &str) -> Result<usize> {
+    Ok(count_pairs(&Day4::parse(input)?, partial_overlaps))
+}
+
+fn count_pairs(pairs: &[(Range, Range)], predicate: fn(&Range, &Range) -> bool) -> usize {
+    pairs.iter().filter(|(p1, p2)| predicate(p1, p2)).count()
+}
+
+fn check_range(range: Range) -> Result<Range> {
+    if range.min > range.max {
+        return Err(Error::msg(format!(
+            "Range ends before it starts: {}-{}",
+            range.min, range.max
+        )));
     }
+    Ok(range)
 }
 
-fn make_pairs(line: String) -> (Range, Range) {
-    let (p1, p2) = line.split_once(',').unwrap();
-    (make_pair(p1), make_pair(p2))
+fn make_pairs(line: &str) -> Result<(Range, Range)> {
+    let Pair { first, second } = line.parse()?;
+    Ok((check_range(first)?, check_range(second)?))
 }
 
 fn overlaps(p1: &Range, p2: &Range) -> bool {
-    if p1.min <= p2.min && p1.max >= p2.max {
-        true
-    } else if p2.min <= p1.min && p2.max >= p1.max {
-        true
-    } else {
-        false
-    }
+    (p1.min <= p2.min && p1.max >= p2.max) || (p2.min <= p1.min && p2.max >= p1.max)
 }
 
 fn partial_overlaps(p1: &Range, p2: &Range) -> bool {
-    for i in p1.min..=p1.max {
-        if i >= p2.min && i <= p2.max {
-            return true;
-        }
-    }
-    false
+    p1.min.max(p2.min) <= p1.max.min(p2.max)
+}
+
+/// Writes `size` pairs of section ranges between 1 and 99.
+fn generate_pairs(rng: &mut Rng, size: usize) -> String {
+    let mut range = || {
+        let min = rng.between(1, 99);
+        format!("{}-{}", min, rng.between(min, 99))
+    };
+    let pairs: Vec<String> = (0..size)
+        .map(|_| format!("{},{}", range(), range()))
+        .collect();
+    pairs.join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::files::tests::MockFileReader;
+    use crate::files::InMemoryReader;
 
     #[test]
     fn test_returns_number_of_total_overlapped_pairs() {
         let lines = vec!["1-5,2-3".to_string(), "1-2,4-5".to_string()];
         let day = Day4 {
-            file_reader: Box::new(MockFileReader { lines }),
+            file_reader: Box::new(InMemoryReader::from(lines)),
             file_path: Path::new("some-file.txt"),
         };
         let result = day.run().unwrap();
 
         assert_eq!(result.0, 1);
-
     }
 
     #[test]
     fn test_returns_number_of_partial_overlaps() {
         let lines = vec!["5-7,7-9".to_string(), "2-4,6-8".to_string()];
         let day = Day4 {
-            file_reader: Box::new(MockFileReader { lines }),
+            file_reader: Box::new(InMemoryReader::from(lines)),
             file_path: Path::new("some-file.txt"),
         };
         let result = day.run().unwrap();
@@ -107,13 +162,42 @@ mod tests {
 
     #[test]
     fn test_make_pair() {
-        let line = "1-5,2-3".to_string();
-        let result = make_pairs(line);
+        let line = "1-5,2-3";
+        let result = make_pairs(line).unwrap();
 
         assert_eq!(result.0, Range { min: 1, max: 5 });
         assert_eq!(result.1, Range { min: 2, max: 3 });
     }
 
+    #[test]
+    fn test_make_pair_returns_error_for_invalid_range() {
+        let result = make_pairs("1-5,23");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_make_pair_returns_error_for_backwards_range() {
+        let result = make_pairs("5-1,2-3");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_counts_overlaps_of_huge_ranges() {
+        let input = format!("0-{},1-2", usize::MAX);
+
+        assert_eq!(count_partial_overlaps(&input).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_counts_overlaps_from_text() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        assert_eq!(count_overlaps(input).unwrap(), 2);
+        assert_eq!(count_partial_overlaps(input).unwrap(), 4);
+    }
+
     mod overlaps {
         use super::*;
 
@@ -124,7 +208,7 @@ mod tests {
 
             let result = overlaps(&p1, &p2);
 
-            assert_eq!(result, true);
+            assert!(result);
         }
 
         #[test]
@@ -134,7 +218,7 @@ mod tests {
 
             let result = overlaps(&p1, &p2);
 
-            assert_eq!(result, false);
+            assert!(!result);
         }
     }
 
@@ -148,7 +232,7 @@ mod tests {
 
             let result = partial_overlaps(&p1, &p2);
 
-            assert_eq!(result, true);
+            assert!(result);
         }
 
         #[test]
@@ -158,7 +242,46 @@ mod tests {
 
             let result = partial_overlaps(&p1, &p2);
 
-            assert_eq!(result, false);
+            assert!(!result);
+        }
+    }
+
+    #[test]
+    fn test_generates_valid_pairs() {
+        let input = generate_pairs(&mut Rng::new(1), 25);
+
+        assert_eq!(Day4::parse(&input).unwrap().len(), 25);
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn range() -> impl Strategy<Value = Range> {
+            (0..100usize, 0..100usize).prop_map(|(a, b)| Range {
+                min: a.min(b),
+                max: a.max(b),
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn test_overlaps_implies_partial_overlaps(p1 in range(), p2 in range()) {
+                prop_assert!(!overlaps(&p1, &p2) || partial_overlaps(&p1, &p2));
+            }
+
+            #[test]
+            fn test_overlaps_are_symmetric(p1 in range(), p2 in range()) {
+                prop_assert_eq!(overlaps(&p1, &p2), overlaps(&p2, &p1));
+                prop_assert_eq!(partial_overlaps(&p1, &p2), partial_overlaps(&p2, &p1));
+            }
+
+            #[test]
+            fn test_parses_written_pairs(p1 in range(), p2 in range()) {
+                let line = format!("{}-{},{}-{}", p1.min, p1.max, p2.min, p2.max);
+
+                prop_assert_eq!(make_pairs(&line).unwrap(), (p1, p2));
+            }
         }
     }
 }
@@ -1,8 +1,10 @@
-use super::files::FileReader;
-use super::Day;
-use anyhow::Result;
+use super::files::{FileReader, ReadsInput};
+use super::generate::Rng;
+use super::parse::{self, Block};
+use super::{Answer, Day, Solution};
+use anyhow::{Context, Error, Result};
+use serde::Serialize;
 use std::path::Path;
-use regex::Regex;
 
 pub struct Day5 {
     pub file_reader: Box<dyn FileReader>,
@@ -18,37 +20,110 @@ impl Default for Day5 {
     }
 }
 
+impl ReadsInput for Day5 {
+    fn with_reader(file_reader: Box<dyn FileReader>) -> Self {
+        Self {
+            file_reader,
+            ..Self::default()
+        }
+    }
+}
+
 impl Day<String> for Day5 {
     fn run(&self) -> Result<String> {
-        let input = self.file_reader.read_file(self.file_path)?;
-        let (stacks, instructions) = Parser::split_input(input);
-        let mut stacks = Parser::parse_stacks(stacks);
-        let instructions = Parser::parse_instructions(instructions);
-        execute_instructions(&mut stacks, instructions);
-        let top_string = tops(stacks);
+        let input = self.file_reader.read_to_string(self.file_path)?;
+        let top_string = top_crates(&input)?;
         println!("{}", top_string);
         Ok(top_string)
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct Instruction {
+impl Solution for Day5 {
+    const DAY: usize = 5;
+    const VERSION: u32 = 1;
+    const TITLE: &'static str = "Supply Stacks";
+    type Parsed = Supplies;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let (stacks, count, instructions) = Parser::split_input(input)?;
+        Ok(Supplies {
+            stacks: Parser::parse_stacks(&stacks, count)?,
+            instructions: Parser::parse_instructions(&instructions)?,
+        })
+    }
+
+    fn part_1(supplies: &Self::Parsed) -> Result<Answer> {
+        Ok(rearrange(supplies)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_supplies(rng, size))
+    }
+}
+
+/// Moves crates one at a time and reads the crate on top of each stack.
+pub fn top_crates(input: &str) -> Result<String> {
+    rearrange(&Day5::parse(input)?)
+}
+
+/// The starting stacks of crates, bottom first, and the moves to make.
+#[derive(Debug, Serialize)]
+pub struct Supplies {
+    pub stacks: Vec<Vec<char>>,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Instruction {
     pub quantity: usize,
     pub from: usize,
     pub to: usize,
 }
 
+crate::line_pattern! {
+    "move {quantity} from {from} to {to}"
+    /// An instruction as written, with stacks numbered from 1.
+    struct Move {
+        quantity: usize,
+        from: usize,
+        to: usize,
+    }
+}
+
 struct Parser {}
 
+fn rearrange(supplies: &Supplies) -> Result<String> {
+    let mut stacks = supplies.stacks.clone();
+    execute_instructions(&mut stacks, &supplies.instructions)?;
+    Ok(tops(stacks))
+}
 
-fn execute_instructions(stacks: &mut Vec<Vec<char>>, instructions: Vec<Instruction>) {
+fn execute_instructions(stacks: &mut [Vec<char>], instructions: &[Instruction]) -> Result<()> {
     for instruction in instructions {
-        let from_stack = stacks.get_mut(instruction.from).unwrap();
-        let mut moved = from_stack.split_off(from_stack.len() - instruction.quantity);
-        let to_stack = stacks.get_mut(instruction.to).unwrap();
+        let missing_stack =
+            |stack: usize| Error::msg(format!("No stack {} in {:?}", stack + 1, instruction));
+        let from_stack = stacks
+            .get_mut(instruction.from)
+            .ok_or_else(|| missing_stack(instruction.from))?;
+        let remaining = from_stack
+            .len()
+            .checked_sub(instruction.quantity)
+            .ok_or_else(|| {
+                Error::msg(format!(
+                    "Stack {} has only {} crates for {:?}",
+                    instruction.from + 1,
+                    from_stack.len(),
+                    instruction
+                ))
+            })?;
+        let mut moved = from_stack.split_off(remaining);
+        let to_stack = stacks
+            .get_mut(instruction.to)
+            .ok_or_else(|| missing_stack(instruction.to))?;
         moved.reverse();
         to_stack.extend(moved);
     }
+    Ok(())
 }
 
 fn tops(stacks: Vec<Vec<char>>) -> String {
@@ -62,60 +137,128 @@ fn tops(stacks: Vec<Vec<char>>) -> String {
 }
 
 impl Parser {
-    fn split_input(input: Vec<String>) -> (Vec<String>, Vec<String>) {
-        let mut stacks = Vec::new();
-        let mut instructions = Vec::new();
-        for line in input {
-            if line.contains('[') {
-                stacks.push(line);
-            } else if !line.is_empty() {
-                instructions.push(line);
-            }
-        }
-
-        instructions.remove(0);
-        (stacks, instructions)
+    /// Splits the drawing of the stacks from the instructions, and reads how
+    /// many stacks there are from the last of the stack numbers under it.
+    fn split_input(input: &str) -> Result<(Vec<&str>, usize, Block<'_>)> {
+        let (mut drawing, instructions) = parse::header_body(input);
+        let numbers = drawing
+            .pop()
+            .map_err(|_| Error::msg("Missing stack numbers"))?;
+        let count = numbers
+            .split_whitespace()
+            .last()
+            .ok_or_else(|| Error::msg("Missing stack numbers"))?
+            .parse()
+            .with_context(|| format!("Invalid stack numbers {:?}", numbers))?;
+        Ok((drawing.lines, count, instructions))
     }
 
-    fn parse_stacks(input: Vec<String>) -> Vec<Vec<char>> {
-        let length = input[0].len();
-        let mut result = vec![Vec::new(); length/3];
-        for line in input {
-            let mut i = 1;
-            while i < length {
-                let letter = line.chars().nth(i).unwrap();
-                if letter != ' ' {
-                    if let Some(stack) = result.get_mut(i / 4) {
-                        stack.insert(0, letter);
-                    }
+    /// Reads `count` stacks from the drawing. Trailing whitespace may be
+    /// trimmed when reading, so stacks on the right can be missing from a
+    /// line, but no line may draw more stacks than there are.
+    fn parse_stacks(input: &[&str], count: usize) -> Result<Vec<Vec<char>>> {
+        // Each stack takes three characters plus a space between stacks.
+        let rows: Vec<Vec<&str>> = input
+            .iter()
+            .map(|line| parse::columns(line, 3, 1))
+            .collect();
+        for (number, row) in (1..).zip(&rows) {
+            if row.len() > count {
+                return Err(Error::msg(format!(
+                    "Line {} draws {} stacks, but there are only {}",
+                    number,
+                    row.len(),
+                    count
+                )));
+            }
+        }
+        let mut result = vec![Vec::new(); count];
+        for row in rows.iter().rev() {
+            for (stack, cell) in result.iter_mut().zip(row) {
+                if let Some(letter) = cell.chars().nth(1).filter(|letter| *letter != ' ') {
+                    stack.push(letter);
                 }
-                i += 4;
             }
         }
 
-        result
+        Ok(result)
     }
 
-    fn parse_instructions(input: Vec<String>) -> Vec<Instruction> {
-        let re = Regex::new(r"move (\d+) from (\d+) to (\d+)$").unwrap();
-        let mut instructions = Vec::new();
-        for line in input {
-            let caps = re.captures(&line).unwrap();
-            instructions.push(Instruction {
-                quantity: caps[1].parse().unwrap(),
-                from: caps[2].parse::<usize>().unwrap() - 1, 
-                to: caps[3].parse::<usize>().unwrap() - 1, 
-            });
-        }
+    fn parse_instructions(input: &Block) -> Result<Vec<Instruction>> {
+        input.parse_lines(|line| {
+            let Move { quantity, from, to } = line.parse()?;
+            Ok(Instruction {
+                quantity,
+                from: Parser::stack_index(from)?,
+                to: Parser::stack_index(to)?,
+            })
+        })
+    }
 
-       instructions 
+    /// Turns a stack number, counted from 1, into an index.
+    fn stack_index(number: usize) -> Result<usize> {
+        number
+            .checked_sub(1)
+            .ok_or_else(|| Error::msg("Stacks are numbered from 1"))
+    }
+}
+
+/// Draws nine stacks of crates and writes `size` moves, none of which takes
+/// the last crate off a stack.
+fn generate_supplies(rng: &mut Rng, size: usize) -> String {
+    let letters: Vec<char> = ('A'..='Z').collect();
+    let mut stacks: Vec<Vec<char>> = (0..9)
+        .map(|_| (0..rng.between(2, 8)).map(|_| rng.pick(&letters)).collect())
+        .collect();
+    let drawn = stacks.clone();
+    let mut instructions = Vec::new();
+    for _ in 0..size {
+        // There are more crates than stacks, so some stack always has two.
+        let movable: Vec<usize> = (0..stacks.len()).filter(|&i| stacks[i].len() > 1).collect();
+        let from = rng.pick(&movable);
+        let to = (from + rng.between(1, stacks.len() - 1)) % stacks.len();
+        let quantity = rng.between(1, stacks[from].len() - 1);
+        let instruction = Instruction { quantity, from, to };
+        execute_instructions(&mut stacks, std::slice::from_ref(&instruction)).unwrap();
+        instructions.push(instruction);
+    }
+    draw(&drawn, &instructions)
+}
+
+/// Writes `stacks` and `instructions` out the way the puzzle input does,
+/// with the stacks numbered under the drawing and a blank line before the
+/// moves.
+fn draw(stacks: &[Vec<char>], instructions: &[Instruction]) -> String {
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    for level in (0..height).rev() {
+        let row: Vec<String> = stacks
+            .iter()
+            .map(|stack| match stack.get(level) {
+                Some(letter) => format!("[{}]", letter),
+                None => "   ".to_string(),
+            })
+            .collect();
+        lines.push(row.join(" "));
+    }
+    let numbers: Vec<String> = (1..=stacks.len()).map(|n| format!(" {} ", n)).collect();
+    lines.push(numbers.join(" "));
+    lines.push(String::new());
+    for instruction in instructions {
+        lines.push(format!(
+            "move {} from {} to {}",
+            instruction.quantity,
+            instruction.from + 1,
+            instruction.to + 1
+        ));
     }
+    lines.join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::files::tests::MockFileReader;
+    use crate::files::InMemoryReader;
 
     #[test]
     fn test_returns_top_of_stacks() {
@@ -131,7 +274,7 @@ mod tests {
             "move 1 from 1 to 2".to_string(),
         ];
         let day = Day5 {
-            file_reader: Box::new(MockFileReader { lines }),
+            file_reader: Box::new(InMemoryReader::from(lines)),
             file_path: Path::new("some-file.txt"),
         };
         let result = day.run().unwrap();
@@ -141,41 +284,48 @@ mod tests {
 
     #[test]
     fn test_execute_instructions() {
-        let mut stacks = vec![
-            vec!['Z', 'N'],
-            vec!['D', 'C', 'M'],
-        ];
-        let instructions = vec![
-            Instruction {
-                quantity: 3,
-                from: 1,
-                to: 0,
-            },
-        ];
-        execute_instructions(&mut stacks, instructions);
-        assert_eq!(stacks, vec![
-            vec!['Z', 'N', 'M', 'C', 'D'],
-            vec![],
-        ]);
+        let mut stacks = vec![vec!['Z', 'N'], vec!['D', 'C', 'M']];
+        let instructions = vec![Instruction {
+            quantity: 3,
+            from: 1,
+            to: 0,
+        }];
+        execute_instructions(&mut stacks, &instructions).unwrap();
+        assert_eq!(stacks, vec![vec!['Z', 'N', 'M', 'C', 'D'], vec![],]);
+    }
+
+    #[test]
+    fn test_execute_instructions_returns_error_for_missing_crates() {
+        let mut stacks = vec![vec!['Z'], vec![]];
+        let instructions = vec![Instruction {
+            quantity: 2,
+            from: 0,
+            to: 1,
+        }];
+        assert!(execute_instructions(&mut stacks, &instructions).is_err());
+    }
+
+    #[test]
+    fn test_execute_instructions_returns_error_for_missing_stack() {
+        let mut stacks = vec![vec!['Z'], vec![]];
+        let instructions = vec![Instruction {
+            quantity: 1,
+            from: 0,
+            to: 7,
+        }];
+        assert!(execute_instructions(&mut stacks, &instructions).is_err());
     }
 
     #[test]
     fn test_tops() {
-        let stacks = vec![
-            vec!['Z', 'N', 'M'],
-            vec!['D', 'C'],
-        ];
+        let stacks = vec![vec!['Z', 'N', 'M'], vec!['D', 'C']];
         let result = tops(stacks);
         assert_eq!(result, "MC");
     }
 
-
     #[test]
     fn test_tops_empty() {
-        let stacks = vec![
-            vec!['Z', 'N', 'M'],
-            vec![],
-        ];
+        let stacks = vec![vec!['Z', 'N', 'M'], vec![]];
         let result = tops(stacks);
         assert_eq!(result, "M");
     }
@@ -185,24 +335,27 @@ mod tests {
 
         #[test]
         fn test_split_input_lines() {
-            let lines = vec![
-                "[D]        ".to_string(),
-                "[N] [C]    ".to_string(),
-                " 1   2   3".to_string(),
-                "".to_string(),
-                "move 1 from 2 to 1".to_string(),
-                "move 3 from 1 to 3".to_string(),
-            ];
-
-            let result = Parser::split_input(lines);
+            let lines = [
+                "[D]        ",
+                "[N] [C]    ",
+                " 1   2   3",
+                "",
+                "move 1 from 2 to 1",
+                "move 3 from 1 to 3",
+            ]
+            .join("\n");
+
+            let result = Parser::split_input(&lines).unwrap();
 
             assert_eq!(
                 result.0,
                 vec!["[D]        ".to_string(), "[N] [C]    ".to_string(),]
             );
 
+            assert_eq!(result.1, 3);
+
             assert_eq!(
-                result.1,
+                result.2.lines,
                 vec![
                     "move 1 from 2 to 1".to_string(),
                     "move 3 from 1 to 3".to_string(),
@@ -212,13 +365,9 @@ mod tests {
 
         #[test]
         fn test_parse_stacks() {
-            let stacks = vec![
-                "    [D]    ".to_string(),
-                "[N] [C]    ".to_string(),
-                "[Z] [M] [P]".to_string(),
-            ];
+            let stacks = vec!["    [D]    ", "[N] [C]    ", "[Z] [M] [P]"];
 
-            let result = Parser::parse_stacks(stacks);
+            let result = Parser::parse_stacks(&stacks, 3).unwrap();
 
             assert_eq!(
                 result,
@@ -230,14 +379,45 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_keeps_empty_last_stack() {
+            let result = crate::solve(5, 1, "[A]\n 1   2 \n\nmove 1 from 1 to 2").unwrap();
+
+            assert_eq!(result, "A".into());
+        }
+
+        #[test]
+        fn test_parse_stacks_returns_error_for_more_stacks_than_numbered() {
+            let result = Parser::parse_stacks(&["[A] [B] [C]"], 2);
+
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn test_split_input_returns_error_for_invalid_stack_numbers() {
+            assert!(Parser::split_input("[A]\n\nmove 1 from 1 to 1").is_err());
+            assert!(Parser::split_input("[A]\n one\n\nmove 1 from 1 to 1").is_err());
+        }
+
+        #[test]
+        fn test_parse_instructions_returns_error_for_invalid_line() {
+            let result = Parser::parse_instructions(&Block::new("move one from 2 to 1"));
+
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn test_parse_instructions_returns_error_for_stack_zero() {
+            let result = Parser::parse_instructions(&Block::new("move 1 from 0 to 1"));
+
+            assert!(result.is_err());
+        }
+
         #[test]
         fn test_parse_instructions() {
-            let instructions = vec![
-                "move 1 from 2 to 1".to_string(),
-                "move 3 from 1 to 3".to_string(),
-            ];
+            let instructions = Block::new("move 1 from 2 to 1\nmove 3 from 1 to 3");
 
-            let result = Parser::parse_instructions(instructions);
+            let result = Parser::parse_instructions(&instructions).unwrap();
 
             assert_eq!(
                 result,
@@ -256,4 +436,74 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_generated_moves_never_empty_a_stack() {
+        for seed in 0..20 {
+            let input = generate_supplies(&mut Rng::new(seed), 200);
+            let mut supplies = Day5::parse(&input).unwrap();
+
+            assert_eq!(supplies.stacks.len(), 9);
+            assert_eq!(supplies.instructions.len(), 200);
+            for instruction in &supplies.instructions {
+                execute_instructions(&mut supplies.stacks, std::slice::from_ref(instruction))
+                    .unwrap();
+                assert!(supplies.stacks.iter().all(|stack| !stack.is_empty()));
+            }
+        }
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// Stacks, and moves that never take more crates than a stack holds.
+        fn program() -> impl Strategy<Value = (Vec<Vec<char>>, Vec<Instruction>)> {
+            let stacks = prop::collection::vec(
+                prop::collection::vec(prop::char::range('A', 'Z'), 1..8),
+                1..10,
+            );
+            let moves =
+                prop::collection::vec((any::<usize>(), any::<usize>(), any::<usize>()), 0..30);
+            (stacks, moves).prop_map(|(stacks, moves)| {
+                let mut sizes: Vec<usize> = stacks.iter().map(|stack| stack.len()).collect();
+                let mut instructions = Vec::new();
+                for (from, to, quantity) in moves {
+                    let from = from % sizes.len();
+                    let to = to % sizes.len();
+                    let quantity = quantity % (sizes[from] + 1);
+                    sizes[from] -= quantity;
+                    sizes[to] += quantity;
+                    instructions.push(Instruction { quantity, from, to });
+                }
+                (stacks, instructions)
+            })
+        }
+
+        fn sorted_crates(stacks: &[Vec<char>]) -> Vec<char> {
+            let mut crates: Vec<char> = stacks.iter().flatten().copied().collect();
+            crates.sort_unstable();
+            crates
+        }
+
+        proptest! {
+            #[test]
+            fn test_execute_instructions_conserves_crates((stacks, instructions) in program()) {
+                let mut moved = stacks.clone();
+
+                execute_instructions(&mut moved, &instructions).unwrap();
+
+                prop_assert_eq!(moved.len(), stacks.len());
+                prop_assert_eq!(sorted_crates(&moved), sorted_crates(&stacks));
+            }
+
+            #[test]
+            fn test_parses_drawn_stacks_and_moves((stacks, instructions) in program()) {
+                let supplies = Day5::parse(&draw(&stacks, &instructions)).unwrap();
+
+                prop_assert_eq!(supplies.stacks, stacks);
+                prop_assert_eq!(supplies.instructions, instructions);
+            }
+        }
+    }
 }
@@ -1,6 +1,8 @@
-use super::files::FileReader;
-use super::Day;
-use anyhow::Result;
+use super::files::{FileReader, ReadsInput};
+use super::generate::Rng;
+use super::{Answer, Day, Solution};
+use anyhow::{Error, Result};
+use std::collections::HashMap;
 use std::path::Path;
 
 pub struct Day6 {
@@ -17,21 +19,100 @@ impl Default for Day6 {
     }
 }
 
+impl ReadsInput for Day6 {
+    fn with_reader(file_reader: Box<dyn FileReader>) -> Self {
+        Self {
+            file_reader,
+            ..Self::default()
+        }
+    }
+}
+
 impl Day<usize> for Day6 {
     fn run(&self) -> Result<usize> {
-        let input = self.file_reader.read_file(self.file_path)?;
-        let signal = input.first().unwrap();
-        let mut four_slice = "notunique";
-        let mut i = 0;
-        while !unique(four_slice) {
-            four_slice = &signal[i..i+4];
-            i+=1;
+        let input = self.file_reader.read_to_string(self.file_path)?;
+        let position = start_of_packet(&input)?;
+        println!("Seen at char {}", position);
+        Ok(position)
+    }
+}
+
+impl Solution for Day6 {
+    const DAY: usize = 6;
+    const VERSION: u32 = 1;
+    const TITLE: &'static str = "Tuning Trouble";
+    const NAME: &'static str = "unique";
+    /// The datastream from the first line of the input.
+    type Parsed = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let signal = input
+            .lines()
+            .next()
+            .ok_or_else(|| Error::msg("Missing signal"))?;
+        Ok(signal.to_string())
+    }
+
+    fn part_1(signal: &Self::Parsed) -> Result<Answer> {
+        Ok(find_marker(signal)?.into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_signal(rng, size))
+    }
+}
+
+/// Finds the marker with a sliding window instead of checking each window
+/// from scratch.
+pub struct Day6Window;
+
+impl Solution for Day6Window {
+    const DAY: usize = 6;
+    const VERSION: u32 = 1;
+    const TITLE: &'static str = "Tuning Trouble";
+    const NAME: &'static str = "window";
+    type Parsed = String;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Day6::parse(input)
+    }
+
+    fn part_1(signal: &Self::Parsed) -> Result<Answer> {
+        Ok(find_marker_window(signal, 4)?.into())
+    }
+}
+
+/// Counts the characters read before the first four unique characters end.
+pub fn start_of_packet(input: &str) -> Result<usize> {
+    find_marker(&Day6::parse(input)?)
+}
+
+fn find_marker(signal: &str) -> Result<usize> {
+    let chars: Vec<char> = signal.chars().collect();
+    for (i, window) in chars.windows(4).enumerate() {
+        if unique(&window.iter().collect::<String>()) {
+            return Ok(i + 4);
         }
+    }
 
-        println!("{} is unique", four_slice);
-        println!("Seen at char {}", i+3);
-        Ok(i+3)
+    Err(Error::msg("No start-of-packet marker found"))
+}
+
+/// Keeps the window free of repeats by moving its start past the last
+/// sighting of each new character.
+fn find_marker_window(signal: &str, length: usize) -> Result<usize> {
+    let mut last_seen = HashMap::new();
+    let mut start = 0;
+    for (i, c) in signal.chars().enumerate() {
+        if let Some(previous) = last_seen.insert(c, i) {
+            start = start.max(previous + 1);
+        }
+        if i + 1 - start == length {
+            return Ok(i + 1);
+        }
     }
+
+    Err(Error::msg("No start-of-packet marker found"))
 }
 
 fn unique(s: &str) -> bool {
@@ -43,16 +124,37 @@ fn unique(s: &str) -> bool {
     true
 }
 
+/// Writes a signal of `size` characters whose first marker ends after
+/// `marker` characters.
+pub fn signal_with_marker(rng: &mut Rng, size: usize, marker: usize) -> String {
+    let size = size.max(4);
+    let marker = marker.clamp(4, size);
+    let mut letters: Vec<char> = ('a'..='z').collect();
+    rng.shuffle(&mut letters);
+    // Before the marker only its first three letters are used, so no earlier
+    // window of four can be unique.
+    let mut signal: Vec<char> = (0..marker - 4).map(|_| rng.pick(&letters[..3])).collect();
+    signal.extend(&letters[..4]);
+    signal.extend((marker..size).map(|_| rng.pick(&letters)));
+    signal.into_iter().collect()
+}
+
+/// Writes a signal of `size` characters with its marker somewhere in it.
+fn generate_signal(rng: &mut Rng, size: usize) -> String {
+    let marker = rng.between(4, size.max(4));
+    signal_with_marker(rng, size, marker)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::files::tests::MockFileReader;
+    use crate::files::InMemoryReader;
 
     #[test]
     fn test_returns_index_of_start_sequence() {
         let lines = vec!["bvwbjplbgvbhsrlpgdmjqwftvncz".to_string()];
         let day = Day6 {
-            file_reader: Box::new(MockFileReader { lines }),
+            file_reader: Box::new(InMemoryReader::from(lines)),
             file_path: Path::new("some-file.txt"),
         };
 
@@ -61,10 +163,64 @@ mod tests {
         assert_eq!(result, 5);
     }
 
+    #[test]
+    fn test_start_of_packet_from_text() {
+        let result = start_of_packet("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap();
+
+        assert_eq!(result, 7);
+    }
+
+    #[test]
+    fn test_returns_error_without_marker() {
+        assert!(start_of_packet("abc").is_err());
+        assert!(start_of_packet("aaaaaaaa").is_err());
+    }
+
+    #[test]
+    fn test_counts_characters_not_bytes() {
+        assert_eq!(start_of_packet("ééabcd").unwrap(), 5);
+    }
+
     #[test]
     fn test_unique() {
-        assert_eq!(unique("abcd"), true);
-        assert_eq!(unique("abbb"), false);
-        assert_eq!(unique("bvwb"), false);
+        assert!(unique("abcd"));
+        assert!(!unique("abbb"));
+        assert!(!unique("bvwb"));
+    }
+
+    #[test]
+    fn test_generates_marker_at_chosen_position() {
+        for marker in [4, 5, 17, 100] {
+            let signal = signal_with_marker(&mut Rng::new(marker as u64), 100, marker);
+
+            assert_eq!(signal.len(), 100);
+            assert_eq!(start_of_packet(&signal).unwrap(), marker);
+        }
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn naive_marker(signal: &str) -> Option<usize> {
+            let chars: Vec<char> = signal.chars().collect();
+            (0..chars.len().saturating_sub(3)).find_map(|i| {
+                let window = &chars[i..i + 4];
+                let distinct = (0..4).all(|a| (a + 1..4).all(|b| window[a] != window[b]));
+                distinct.then_some(i + 4)
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn test_matches_naive_search(signal in "[a-f]{0,40}") {
+                prop_assert_eq!(find_marker(&signal).ok(), naive_marker(&signal));
+            }
+
+            #[test]
+            fn test_window_matches_naive_search(signal in "[a-f]{0,40}") {
+                prop_assert_eq!(find_marker_window(&signal, 4).ok(), naive_marker(&signal));
+            }
+        }
     }
 }
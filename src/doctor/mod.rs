@@ -0,0 +1,442 @@
+use super::cache;
+use super::config::Config;
+use super::expected;
+use super::files::FileReader;
+use super::runner::{self, Entry};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the checksums of the puzzle inputs are recorded.
+pub const MANIFEST_PATH: &str = "assets/checksums.toml";
+/// The environment variable holding the adventofcode.com session token.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// The file holding the session token when the variable isn't set.
+pub const SESSION_PATH: &str = ".aoc/session";
+
+const BYTE_ORDER_MARK: &str = "\u{feff}";
+
+/// Where to look for everything the health report covers.
+pub struct Settings {
+    pub config: PathBuf,
+    pub manifest: PathBuf,
+    /// The session token from the environment, if set.
+    pub session: Option<String>,
+    pub session_path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// The outcome of a single check.
+#[derive(Debug, PartialEq)]
+pub struct Check {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Checks grouped under a heading, such as one day.
+pub struct Section {
+    pub title: String,
+    pub checks: Vec<Check>,
+}
+
+pub struct Report {
+    pub sections: Vec<Section>,
+}
+
+/// The checksum of each day's input, keyed as `day_N`.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub inputs: BTreeMap<String, String>,
+}
+
+fn ok(message: impl Into<String>) -> Check {
+    Check {
+        severity: Severity::Ok,
+        message: message.into(),
+    }
+}
+
+fn warning(message: impl Into<String>) -> Check {
+    Check {
+        severity: Severity::Warning,
+        message: message.into(),
+    }
+}
+
+fn error(message: impl Into<String>) -> Check {
+    Check {
+        severity: Severity::Error,
+        message: message.into(),
+    }
+}
+
+fn checksum(input: &str) -> String {
+    format!("{:016x}", cache::hash(input))
+}
+
+fn key(entry: &Entry) -> String {
+    format!("day_{}", entry.day)
+}
+
+impl Manifest {
+    /// Checksums every registered day's input that can be read.
+    pub fn record(file_reader: &dyn FileReader) -> Self {
+        let inputs = runner::DAYS
+            .iter()
+            .filter_map(|entry| {
+                let bytes = file_reader.read_bytes(&entry.input_path()).ok()?;
+                let input = String::from_utf8(bytes).ok()?;
+                Some((key(entry), checksum(&input)))
+            })
+            .collect();
+        Self { inputs }
+    }
+
+    pub fn load(file_reader: &dyn FileReader, path: &Path) -> Result<Option<Self>> {
+        let Ok(content) = file_reader.read_to_string(path) else {
+            return Ok(None);
+        };
+        let manifest = toml::from_str(&content)
+            .with_context(|| format!("Invalid checksums in {}", path.display()))?;
+        Ok(Some(manifest))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = format!(
+            "# Checksums of the puzzle inputs, updated by `doctor --record`.\n{}",
+            toml::to_string(self)?
+        );
+        fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Checks every registered day's input and stored answers, the config and
+/// the session token.
+pub fn diagnose(file_reader: &dyn FileReader, settings: &Settings) -> Report {
+    let manifest = Manifest::load(file_reader, &settings.manifest);
+    let mut sections: Vec<Section> = runner::DAYS
+        .iter()
+        .map(|entry| Section {
+            title: format!("Day {}", entry.day),
+            checks: check_day(file_reader, entry, &manifest),
+        })
+        .collect();
+    sections.push(Section {
+        title: "Setup".to_string(),
+        checks: vec![
+            check_manifest(&manifest, &settings.manifest),
+            check_config(file_reader, &settings.config),
+            check_session(file_reader, settings),
+        ],
+    });
+    Report { sections }
+}
+
+fn check_day(
+    file_reader: &dyn FileReader,
+    entry: &Entry,
+    manifest: &Result<Option<Manifest>>,
+) -> Vec<Check> {
+    let path = entry.input_path();
+    let mut checks = Vec::new();
+    let input = match check_input(file_reader, &path) {
+        Ok((check, input)) => {
+            checks.push(check);
+            Some(input)
+        }
+        Err(check) => {
+            checks.push(check);
+            None
+        }
+    };
+
+    if let (Some(input), Ok(Some(manifest))) = (&input, manifest) {
+        checks.push(match manifest.inputs.get(&key(entry)) {
+            Some(recorded) if *recorded == checksum(input) => ok("Checksum matches"),
+            Some(_) => error(format!(
+                "{} changed since its checksum was recorded",
+                path.display()
+            )),
+            None => warning("No checksum recorded"),
+        });
+    }
+
+    let answers = expected::answers_path(&path);
+    checks.push(match expected::read(file_reader, &path) {
+        Ok(Some(expected)) => {
+            let parts: Vec<String> = expected.keys().map(usize::to_string).collect();
+            ok(format!(
+                "Answers for part {} stored in {}",
+                parts.join(" and "),
+                answers.display()
+            ))
+        }
+        Ok(None) => warning(format!("No answers stored in {}", answers.display())),
+        Err(cause) => error(format!("{:#}", cause)),
+    });
+    checks
+}
+
+/// Checks that the input exists and is clean text, returning it if so.
+fn check_input(file_reader: &dyn FileReader, path: &Path) -> Result<(Check, String), Check> {
+    let bytes = file_reader
+        .read_bytes(path)
+        .map_err(|_| error(format!("Missing input {}", path.display())))?;
+    let input = String::from_utf8(bytes)
+        .map_err(|_| error(format!("{} is not valid UTF-8", path.display())))?;
+    if input.trim().is_empty() {
+        return Err(error(format!("{} is empty", path.display())));
+    }
+    if input.starts_with(BYTE_ORDER_MARK) {
+        return Err(error(format!(
+            "{} starts with a byte order mark",
+            path.display()
+        )));
+    }
+    if input.contains("\r\n") {
+        return Err(error(format!("{} has CRLF line endings", path.display())));
+    }
+    let lines = input.lines().count();
+    let check = ok(format!(
+        "{}: {} line{}",
+        path.display(),
+        lines,
+        if lines == 1 { "" } else { "s" }
+    ));
+    Ok((check, input))
+}
+
+fn check_manifest(manifest: &Result<Option<Manifest>>, path: &Path) -> Check {
+    match manifest {
+        Ok(Some(manifest)) => ok(format!(
+            "{} checksums recorded in {}",
+            manifest.inputs.len(),
+            path.display()
+        )),
+        Ok(None) => warning(format!(
+            "No checksums in {}, record them with `doctor --record`",
+            path.display()
+        )),
+        Err(cause) => error(format!("{:#}", cause)),
+    }
+}
+
+fn check_config(file_reader: &dyn FileReader, path: &Path) -> Check {
+    let Ok(content) = file_reader.read_to_string(path) else {
+        return warning(format!("No config at {}, using defaults", path.display()));
+    };
+    match Config::parse(&content) {
+        Ok(_) => ok(format!("Config {} is valid", path.display())),
+        Err(cause) => error(format!("Invalid config in {}: {:#}", path.display(), cause)),
+    }
+}
+
+fn check_session(file_reader: &dyn FileReader, settings: &Settings) -> Check {
+    let from_file = || {
+        file_reader
+            .read_to_string(&settings.session_path)
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+    };
+    match (&settings.session, from_file()) {
+        (Some(token), _) if !token.trim().is_empty() => {
+            ok(format!("Session token set in {}", SESSION_VAR))
+        }
+        (_, Some(_)) => ok(format!(
+            "Session token found in {}",
+            settings.session_path.display()
+        )),
+        _ => warning(format!(
+            "No session token, set {} or write it to {}",
+            SESSION_VAR,
+            settings.session_path.display()
+        )),
+    }
+}
+
+impl Report {
+    fn count(&self, severity: Severity) -> usize {
+        self.sections
+            .iter()
+            .flat_map(|section| &section.checks)
+            .filter(|check| check.severity == severity)
+            .count()
+    }
+
+    pub fn errors(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Severity::Ok => "ok",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for section in &self.sections {
+            writeln!(f, "{}", section.title)?;
+            for check in &section.checks {
+                writeln!(f, "  {:<8} {}", check.severity, check.message)?;
+            }
+        }
+        match (self.errors(), self.warnings()) {
+            (0, 0) => writeln!(f, "Healthy"),
+            (0, warnings) => writeln!(f, "Healthy with {} warnings", warnings),
+            (errors, warnings) => {
+                writeln!(f, "{} problems and {} warnings", errors, warnings)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::InMemoryReader;
+    use std::collections::HashMap;
+
+    fn settings() -> Settings {
+        Settings {
+            config: PathBuf::from("aoc.toml"),
+            manifest: PathBuf::from(MANIFEST_PATH),
+            session: None,
+            session_path: PathBuf::from(SESSION_PATH),
+        }
+    }
+
+    /// A reader with clean inputs and answers for every day, with `files`
+    /// added or replaced.
+    fn reader(files: &[(&str, &str)]) -> InMemoryReader {
+        let mut contents = HashMap::new();
+        for entry in runner::DAYS {
+            let path = entry.input_path();
+            contents.insert(expected::answers_path(&path), "part_1 = 1\n".to_string());
+            contents.insert(path, format!("day {}\n", entry.day));
+        }
+        for (path, content) in files {
+            contents.insert(PathBuf::from(path), content.to_string());
+        }
+        InMemoryReader::from(contents)
+    }
+
+    fn day(report: &Report, day: usize) -> &[Check] {
+        &report.sections[day - 1].checks
+    }
+
+    #[test]
+    fn test_reports_healthy_days() {
+        let report = diagnose(&reader(&[]), &settings());
+
+        assert_eq!(report.errors(), 0);
+        assert_eq!(
+            day(&report, 4),
+            &[
+                ok("assets/day_4/input.txt: 1 line"),
+                ok("Answers for part 1 stored in assets/day_4/input.answers.toml"),
+            ]
+        );
+        // No manifest, config or session token.
+        assert_eq!(report.warnings(), 3);
+        assert!(report.to_string().ends_with("Healthy with 3 warnings\n"));
+    }
+
+    #[test]
+    fn test_reports_unclean_inputs() {
+        let reader = reader(&[
+            ("assets/day_1/input.txt", ""),
+            ("assets/day_2/input.txt", "\u{feff}A X\n"),
+            ("assets/day_3/input.txt", "abcd\r\nefgh\r\n"),
+        ]);
+
+        let report = diagnose(&reader, &settings());
+
+        assert_eq!(report.errors(), 3);
+        assert!(day(&report, 1)[0].message.ends_with("is empty"));
+        assert!(day(&report, 2)[0].message.ends_with("byte order mark"));
+        assert!(day(&report, 3)[0].message.ends_with("CRLF line endings"));
+    }
+
+    #[test]
+    fn test_reports_missing_input_and_answers() {
+        let mut files: HashMap<PathBuf, String> = HashMap::new();
+        files.insert(PathBuf::from("assets/day_6/input.txt"), "abcd".to_string());
+
+        let report = diagnose(&InMemoryReader::from(files), &settings());
+
+        assert_eq!(
+            day(&report, 1)[0],
+            error("Missing input assets/day_1/input.txt")
+        );
+        assert_eq!(day(&report, 6)[0].severity, Severity::Ok);
+        assert_eq!(
+            day(&report, 6)[1],
+            warning("No answers stored in assets/day_6/input.answers.toml")
+        );
+    }
+
+    #[test]
+    fn test_compares_checksums_with_manifest() {
+        let manifest = toml::to_string(&Manifest::record(&reader(&[]))).unwrap();
+        let reader = reader(&[
+            (MANIFEST_PATH, &manifest),
+            ("assets/day_5/input.txt", "edited\n"),
+        ]);
+
+        let report = diagnose(&reader, &settings());
+
+        assert_eq!(day(&report, 4)[1], ok("Checksum matches"));
+        assert_eq!(
+            day(&report, 5)[1],
+            error("assets/day_5/input.txt changed since its checksum was recorded")
+        );
+        assert_eq!(report.errors(), 1);
+    }
+
+    #[test]
+    fn test_checks_config_and_session() {
+        let reader = reader(&[("aoc.toml", "timeut = 1\n"), (SESSION_PATH, "abc\n")]);
+
+        let report = diagnose(&reader, &settings());
+
+        let setup = &report.sections.last().unwrap().checks;
+        assert_eq!(setup[1].severity, Severity::Error);
+        assert_eq!(setup[2], ok("Session token found in .aoc/session"));
+    }
+
+    #[test]
+    fn test_finds_session_token_in_environment() {
+        let settings = Settings {
+            session: Some("abc".to_string()),
+            ..settings()
+        };
+
+        let report = diagnose(&reader(&[]), &settings);
+
+        let setup = &report.sections.last().unwrap().checks;
+        assert_eq!(setup[2], ok("Session token set in AOC_SESSION"));
+    }
+}
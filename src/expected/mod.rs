@@ -0,0 +1,205 @@
+use super::files::FileReader;
+use super::runner::DayReport;
+use super::{Answer, NotImplemented};
+use anyhow::{Context, Error, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The answers expected for an input, by part.
+pub type Expected = BTreeMap<usize, Answer>;
+
+/// The `n`th example input for a day.
+pub fn example_path(day: usize, n: usize) -> PathBuf {
+    PathBuf::from(format!("assets/day_{}/example_{}.txt", day, n))
+}
+
+/// Every `example_N.txt` in the day's assets, in order.
+pub fn examples(day: usize) -> Result<Vec<PathBuf>> {
+    let dir = PathBuf::from(format!("assets/day_{}", day));
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut examples = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Failed to list {}", dir.display()))? {
+        let path = entry?.path();
+        let n = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("example_"))
+            .and_then(|name| name.strip_suffix(".txt"))
+            .and_then(|n| n.parse::<usize>().ok());
+        if let Some(n) = n {
+            examples.push((n, path));
+        }
+    }
+    examples.sort();
+    Ok(examples.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Where the answers for `input` are kept: `example_1.txt` has its answers in
+/// `example_1.answers.toml`.
+pub fn answers_path(input: &Path) -> PathBuf {
+    input.with_extension("answers.toml")
+}
+
+/// Loads the answers stored alongside `input`, if there are any.
+pub fn load(input: &Path) -> Result<Option<Expected>> {
+    let path = answers_path(input);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&content)
+        .map(Some)
+        .with_context(|| format!("Invalid answers in {}", path.display()))
+}
+
+/// Like [`load`], but reads through `file_reader`, treating an answers file
+/// it can't read as missing.
+pub fn read(file_reader: &dyn FileReader, input: &Path) -> Result<Option<Expected>> {
+    let path = answers_path(input);
+    let Ok(content) = file_reader.read_to_string(&path) else {
+        return Ok(None);
+    };
+    parse(&content)
+        .map(Some)
+        .with_context(|| format!("Invalid answers in {}", path.display()))
+}
+
+/// Parses answers written as `part_1 = 24000` or `part_1 = "CMZ"`.
+pub fn parse(content: &str) -> Result<Expected> {
+    let table: toml::Table = toml::from_str(content)?;
+    let mut expected = Expected::new();
+    for (key, value) in table {
+        let part = key
+            .strip_prefix("part_")
+            .and_then(|part| part.parse().ok())
+            .ok_or_else(|| Error::msg(format!("Unknown key {}, expected part_N", key)))?;
+        let answer = match value {
+            toml::Value::Integer(number) => Answer::Number(
+                number
+                    .try_into()
+                    .with_context(|| format!("Negative answer for {}", key))?,
+            ),
+            toml::Value::String(text) => Answer::Text(text),
+            other => {
+                return Err(Error::msg(format!(
+                    "Answer for {} must be a number or a string, not {}",
+                    key,
+                    other.type_str()
+                )))
+            }
+        };
+        expected.insert(part, answer);
+    }
+    Ok(expected)
+}
+
+/// A part whose answer was not the expected one.
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+    pub part: usize,
+    pub expected: Answer,
+    pub actual: String,
+}
+
+/// Compares the report's answers with the expected ones. Parts that aren't
+/// implemented yet are skipped, so answers can be recorded ahead of time.
+pub fn check(report: &DayReport, expected: &Expected) -> Vec<Mismatch> {
+    expected
+        .iter()
+        .filter_map(|(&part, expected)| {
+            let actual = match report.parts.iter().find(|report| report.part == part) {
+                Some(report) => match &report.answer {
+                    Ok(answer) if answer == expected => return None,
+                    Ok(answer) => answer.to_string(),
+                    Err(error) if error.is::<NotImplemented>() => return None,
+                    Err(error) => format!("error: {:#}", error),
+                },
+                None => "no answer".to_string(),
+            };
+            Some(Mismatch {
+                part,
+                expected: expected.clone(),
+                actual,
+            })
+        })
+        .collect()
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Part {}: expected {}, got {}",
+            self.part, self.expected, self.actual
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner;
+
+    #[test]
+    fn test_parses_numbers_and_text() {
+        let result = parse("part_1 = 24000\npart_2 = \"CMZ\"").unwrap();
+
+        assert_eq!(
+            result,
+            Expected::from([(1, Answer::Number(24000)), (2, Answer::from("CMZ"))])
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys() {
+        assert!(parse("answer = 1").is_err());
+        assert!(parse("part_1 = -1").is_err());
+        assert!(parse("part_1 = 1.5").is_err());
+    }
+
+    #[test]
+    fn test_answers_live_next_to_input() {
+        let result = answers_path(&example_path(4, 2));
+
+        assert_eq!(result, PathBuf::from("assets/day_4/example_2.answers.toml"));
+    }
+
+    #[test]
+    fn test_finds_examples_in_order() {
+        let result = examples(6).unwrap();
+
+        assert_eq!(result.first(), Some(&example_path(6, 1)));
+        assert!(result.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(examples(9999).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_check_reports_wrong_answers_only() {
+        let report = runner::run_input(runner::find(4).unwrap(), "2-4,6-8\n2-8,3-7");
+        let expected = Expected::from([(1, Answer::Number(1)), (2, Answer::Number(2))]);
+
+        let result = check(&report, &expected);
+
+        assert_eq!(
+            result,
+            vec![Mismatch {
+                part: 2,
+                expected: Answer::Number(2),
+                actual: "1".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_check_skips_parts_not_implemented() {
+        let report = runner::run_input(runner::find(6).unwrap(), "mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+        let expected = Expected::from([(1, Answer::Number(7)), (2, Answer::Number(19))]);
+
+        assert!(check(&report, &expected).is_empty());
+    }
+}
@@ -0,0 +1,411 @@
+//! Solvers written as separate programs, so answers from other languages can
+//! be checked against ours.
+//!
+//! A solver is run once per part as `<command...> <day> <part>`, with the
+//! input on stdin, or as `<command...> <day> <part> <path>` when its input is
+//! `path`. It prints the answer and exits successfully, or prints nothing if
+//! it has no solution for that part. Any other exit status is a failure.
+use super::runner::{DayReport, PartReport, Solver, Status};
+use super::{Answer, NotImplemented};
+use anyhow::{Context, Error, Result};
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{self, Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a solver with a time limit is checked for having finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// How an external solver is given its input.
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputMode {
+    #[default]
+    Stdin,
+    Path,
+}
+
+/// An external solver, configured in `aoc.toml`:
+///
+/// ```toml
+/// [[solvers]]
+/// name = "python"
+/// command = ["python3", "solutions/main.py"]
+/// input = "path"
+/// days = [1, 2]
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct External {
+    pub name: String,
+    /// The program and any arguments to start it with.
+    pub command: Vec<String>,
+    #[serde(default)]
+    pub input: InputMode,
+    /// The days it solves, or every day if empty.
+    #[serde(default)]
+    pub days: Vec<usize>,
+}
+
+impl External {
+    pub fn solves(&self, day: usize) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    /// Runs the solver for one part and reads its answer.
+    pub fn solve(&self, day: usize, part: usize, input: &str) -> Result<Answer> {
+        self.solve_until(day, part, input, None)
+    }
+
+    /// Runs every part like [`Solver::run_input`] does, but kills the solver
+    /// once `limit` has passed and reports the day as timed out.
+    pub fn with_limit(&self, limit: Duration) -> Limited<'_> {
+        Limited {
+            external: self,
+            limit,
+        }
+    }
+
+    /// Runs the solver for one part, killing it if it is still running at
+    /// `deadline`.
+    fn solve_until(
+        &self,
+        day: usize,
+        part: usize,
+        input: &str,
+        deadline: Option<Instant>,
+    ) -> Result<Answer> {
+        let (program, arguments) = self
+            .command
+            .split_first()
+            .ok_or_else(|| Error::msg(format!("Solver {} has an empty command", self.name)))?;
+        let mut command = Command::new(program);
+        command
+            .args(arguments)
+            .arg(day.to_string())
+            .arg(part.to_string())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // Kept until the solver is done with it, and removed when dropped.
+        let _input_file = match self.input {
+            InputMode::Stdin => {
+                command.stdin(Stdio::piped());
+                None
+            }
+            InputMode::Path => {
+                let file = TempInput::write(input)?;
+                command.arg(&file.0).stdin(Stdio::null());
+                Some(file)
+            }
+        };
+        let output = self
+            .run(command, input, deadline)
+            .with_context(|| format!("Failed to run solver {}", self.name))?;
+
+        if !output.status.success() {
+            return Err(Error::msg(format!(
+                "Solver {} exited with {}: {}",
+                self.name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let answer = String::from_utf8(output.stdout)
+            .with_context(|| format!("Solver {} printed invalid UTF-8", self.name))?;
+        match answer.trim() {
+            "" => Err(NotImplemented {
+                day,
+                part: Some(part),
+            }
+            .into()),
+            answer => Ok(match answer.parse::<usize>() {
+                Ok(number) => Answer::Number(number),
+                Err(_) => Answer::Text(answer.to_string()),
+            }),
+        }
+    }
+
+    /// Solves both parts, stopping at the first one still running when
+    /// `limit` has passed.
+    fn run_parts(&self, day: usize, input: &str, limit: Option<Duration>) -> DayReport {
+        let start = Instant::now();
+        let deadline = limit.map(|limit| start + limit);
+        let mut report = DayReport::new(day);
+        for part in 1..=2 {
+            let part_start = Instant::now();
+            let answer = self.solve_until(day, part, input, deadline);
+            if let (Some(limit), Err(error)) = (limit, &answer) {
+                if error.is::<TimedOut>() {
+                    report.status = Status::TimedOut(limit);
+                    break;
+                }
+            }
+            report.parts.push(PartReport {
+                part,
+                answer,
+                elapsed: part_start.elapsed(),
+                memory: None,
+                cached: false,
+            });
+        }
+        report.wall = start.elapsed();
+        report
+    }
+
+    fn run(
+        &self,
+        mut command: Command,
+        input: &str,
+        deadline: Option<Instant>,
+    ) -> Result<process::Output> {
+        let mut child = command.spawn()?;
+        // Write on another thread so a solver that prints before reading all
+        // of its input can't deadlock with us.
+        let writer = child.stdin.take().map(|mut stdin| {
+            let input = input.to_string();
+            thread::spawn(move || {
+                let _ = stdin.write_all(input.as_bytes());
+            })
+        });
+        let output = match deadline {
+            Some(deadline) => wait_until(child, deadline)?,
+            None => child.wait_with_output()?,
+        };
+        if let Some(writer) = writer {
+            let _ = writer.join();
+        }
+        Ok(output)
+    }
+}
+
+impl Solver for External {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Solves each part in its own run of the solver. There is no separate
+    /// parse phase, so each part's time includes starting the program.
+    fn run_input(&self, day: usize, input: &str) -> DayReport {
+        self.run_parts(day, input, None)
+    }
+}
+
+/// Waits for `child` to exit, or kills it at `deadline`.
+fn wait_until(mut child: Child, deadline: Instant) -> Result<process::Output> {
+    // Read both pipes as the child writes them, so it never blocks on a
+    // full pipe while we wait.
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        pipe.map(|mut pipe| {
+            thread::spawn(move || {
+                let mut bytes = Vec::new();
+                let _ = pipe.read_to_end(&mut bytes);
+                bytes
+            })
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+    let stderr = drain(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            let _ = child.kill();
+            child.wait()?;
+            return Err(TimedOut.into());
+        }
+        thread::sleep(POLL_INTERVAL.min(deadline - now));
+    };
+    let collect = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+    Ok(process::Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// A solver was killed for running past its time limit.
+#[derive(Debug)]
+struct TimedOut;
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Timed out")
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// A temporary copy of the input for solvers that read it from a path.
+/// Every copy gets its own file, which is removed when dropped.
+struct TempInput(PathBuf);
+
+impl TempInput {
+    fn write(input: &str) -> Result<Self> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let file = Self(env::temp_dir().join(format!(
+            "aoc-{}-{}.txt",
+            process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        )));
+        fs::write(&file.0, input)?;
+        Ok(file)
+    }
+}
+
+impl Drop for TempInput {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// An external solver with a time limit, from [`External::with_limit`].
+pub struct Limited<'a> {
+    external: &'a External,
+    limit: Duration,
+}
+
+impl Solver for Limited<'_> {
+    fn name(&self) -> &str {
+        &self.external.name
+    }
+
+    fn run_input(&self, day: usize, input: &str) -> DayReport {
+        self.external.run_parts(day, input, Some(self.limit))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn shell(name: &str, script: &str, input: InputMode) -> External {
+        External {
+            name: name.to_string(),
+            command: vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                script.to_string(),
+                "sh".to_string(),
+            ],
+            input,
+            days: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_passes_day_part_and_input_on_stdin() {
+        let solver = shell("echo", r#"echo "$1-$2-$(cat)""#, InputMode::Stdin);
+
+        let result = solver.solve(4, 2, "abc").unwrap();
+
+        assert_eq!(result, Answer::from("4-2-abc"));
+    }
+
+    #[test]
+    fn test_passes_input_as_path() {
+        let solver = shell("lines", r#"wc -l < "$3""#, InputMode::Path);
+
+        let result = solver.solve(1, 1, "1\n2\n3\n").unwrap();
+
+        assert_eq!(result, Answer::Number(3));
+    }
+
+    #[test]
+    fn test_empty_answer_is_not_implemented() {
+        let solver = shell("silent", "true", InputMode::Stdin);
+
+        let error = solver.solve(6, 2, "abcd").unwrap_err();
+
+        assert!(error.is::<NotImplemented>());
+    }
+
+    #[test]
+    fn test_reports_failing_solver() {
+        let solver = shell("broken", "echo oops >&2; exit 3", InputMode::Stdin);
+
+        let error = solver.solve(6, 1, "abcd").unwrap_err();
+
+        assert!(error.to_string().contains("oops"));
+    }
+
+    #[test]
+    fn test_runs_both_parts() {
+        let solver = shell("parts", r#"echo "$2""#, InputMode::Stdin);
+
+        let report = solver.run_input(3, "");
+
+        assert_eq!(report.day, 3);
+        let answers: Vec<_> = report
+            .parts
+            .iter()
+            .map(|part| part.answer.as_ref().unwrap().clone())
+            .collect();
+        assert_eq!(answers, vec![Answer::Number(1), Answer::Number(2)]);
+    }
+
+    #[test]
+    fn test_kills_solver_that_runs_too_long() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("finished");
+        let script = format!("sleep 2; touch {}", marker.display());
+        let solver = shell("slow", &script, InputMode::Stdin);
+        let start = Instant::now();
+
+        let report = solver
+            .with_limit(Duration::from_millis(100))
+            .run_input(1, "");
+
+        assert!(matches!(report.status, Status::TimedOut(_)));
+        assert!(report.parts.is_empty());
+        assert!(start.elapsed() < Duration::from_secs(2));
+        thread::sleep(Duration::from_millis(2500));
+        assert!(!marker.exists(), "the solver was left running");
+    }
+
+    #[test]
+    fn test_answers_within_limit() {
+        let solver = shell("parts", r#"echo "$2""#, InputMode::Stdin);
+
+        let report = solver.with_limit(Duration::from_secs(10)).run_input(3, "");
+
+        assert!(matches!(report.status, Status::Completed));
+        assert_eq!(report.parts.len(), 2);
+    }
+
+    #[test]
+    fn test_removes_temporary_input() {
+        let first = TempInput::write("abc").unwrap();
+        let second = TempInput::write("abc").unwrap();
+        assert_ne!(first.0, second.0);
+        let path = first.0.clone();
+
+        drop(first);
+
+        assert!(!path.exists());
+        assert!(second.0.exists());
+    }
+
+    #[test]
+    fn test_solves_listed_days_only() {
+        let mut solver = shell("some", "true", InputMode::Stdin);
+        assert!(solver.solves(9));
+
+        solver.days = vec![1, 2];
+
+        assert!(solver.solves(2));
+        assert!(!solver.solves(3));
+    }
+}
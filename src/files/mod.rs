@@ -1,11 +1,27 @@
-use anyhow::Result;
+use anyhow::{Error, Result};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub trait FileReader {
+/// Reads puzzle input. Readers are shared between the threads running days.
+pub trait FileReader: Send + Sync {
     fn read_file(&self, path: &Path) -> Result<Vec<String>>;
+
+    /// Reads the processed lines back into a single newline separated string.
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        Ok(self.read_file(path)?.join("\n"))
+    }
+
+    /// Reads the file exactly as stored, without processing its lines, so
+    /// line endings and trailing whitespace are kept.
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+}
+
+/// Days that read their puzzle input through a [`FileReader`].
+pub trait ReadsInput {
+    fn with_reader(file_reader: Box<dyn FileReader>) -> Self;
 }
 
 pub struct FileSystemReader;
@@ -13,11 +29,69 @@ impl FileReader for FileSystemReader {
     fn read_file(&self, path: &Path) -> Result<Vec<String>> {
         let file = File::open(path)?;
         let lines = BufReader::new(file).lines();
-        let processed_lines = lines
-            .map(|line| line.unwrap().trim_end().to_string())
+        lines.map(|line| Ok(line?.trim_end().to_string())).collect()
+    }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        Ok(std::fs::read(path)?)
+    }
+}
+
+/// Serves file contents from memory, so days can be driven with example text.
+///
+/// A reader built from a single input returns it for every path, while one
+/// built from a map only knows the paths it was given.
+pub struct InMemoryReader {
+    files: HashMap<PathBuf, String>,
+    fallback: Option<String>,
+}
+
+impl InMemoryReader {
+    fn content(&self, path: &Path) -> Result<&String> {
+        self.files
+            .get(path)
+            .or(self.fallback.as_ref())
+            .ok_or_else(|| Error::msg(format!("No such file: {}", path.display())))
+    }
+}
+
+impl FileReader for InMemoryReader {
+    fn read_file(&self, path: &Path) -> Result<Vec<String>> {
+        let content = self.content(path)?;
+        let processed_lines = content
+            .lines()
+            .map(|line| line.trim_end().to_string())
             .collect();
         Ok(processed_lines)
     }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        Ok(self.content(path)?.clone().into_bytes())
+    }
+}
+
+impl From<&str> for InMemoryReader {
+    fn from(content: &str) -> Self {
+        Self {
+            files: HashMap::new(),
+            fallback: Some(content.to_string()),
+        }
+    }
+}
+
+impl From<Vec<String>> for InMemoryReader {
+    fn from(lines: Vec<String>) -> Self {
+        Self::from(lines.join("\n").as_str())
+    }
+}
+
+impl From<HashMap<PathBuf, String>> for InMemoryReader {
+    fn from(files: HashMap<PathBuf, String>) -> Self {
+        Self {
+            files,
+            fallback: None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -46,13 +120,59 @@ pub mod tests {
         assert_eq!(content.pop().unwrap(), "cool");
     }
 
-    pub struct MockFileReader {
-        pub lines: Vec<String>,
+    #[test]
+    fn test_read_bytes_keeps_line_endings() {
+        let mut tmp_file = NamedTempFile::new().unwrap();
+        write!(tmp_file, "cool \r\n").unwrap();
+
+        let content = FileSystemReader {}.read_bytes(tmp_file.path()).unwrap();
+
+        assert_eq!(content, b"cool \r\n");
     }
 
-    impl FileReader for MockFileReader {
-        fn read_file(&self, _: &Path) -> Result<Vec<String>> {
-            Ok(self.lines.clone())
+    mod in_memory_reader {
+        use super::*;
+
+        #[test]
+        fn test_returns_lines_from_str_for_any_path() {
+            let reader = InMemoryReader::from("first  \nsecond\n");
+
+            let content = reader.read_file(Path::new("any-file.txt")).unwrap();
+
+            assert_eq!(content, vec!["first", "second"]);
+        }
+
+        #[test]
+        fn test_keeps_blank_lines_from_vec() {
+            let reader =
+                InMemoryReader::from(vec!["10".to_string(), "".to_string(), "20".to_string()]);
+
+            let content = reader.read_file(Path::new("any-file.txt")).unwrap();
+
+            assert_eq!(content, vec!["10", "", "20"]);
+        }
+
+        #[test]
+        fn test_returns_content_for_known_path() {
+            let files = HashMap::from([
+                (PathBuf::from("a.txt"), "a".to_string()),
+                (PathBuf::from("b.txt"), "b".to_string()),
+            ]);
+            let reader = InMemoryReader::from(files);
+
+            let content = reader.read_file(Path::new("b.txt")).unwrap();
+
+            assert_eq!(content, vec!["b"]);
+        }
+
+        #[test]
+        fn test_returns_error_for_unknown_path() {
+            let files = HashMap::from([(PathBuf::from("a.txt"), "a".to_string())]);
+            let reader = InMemoryReader::from(files);
+
+            let result = reader.read_file(Path::new("c.txt"));
+
+            assert!(result.is_err());
         }
     }
 }
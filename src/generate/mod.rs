@@ -0,0 +1,119 @@
+use super::runner;
+use super::NotImplemented;
+use anyhow::{Error, Result};
+
+/// A small seeded random number generator (SplitMix64), so generated inputs
+/// are the same on every machine and build.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`. Panics if `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Empty range");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `min..=max`.
+    pub fn between(&mut self, min: usize, max: usize) -> usize {
+        min + self.below(max - min + 1)
+    }
+
+    /// One of `items`, picked at random.
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Generates an input for `day` with about `size` records, the same one for
+/// the same seed.
+pub fn generate(day: usize, seed: u64, size: usize) -> Result<String> {
+    let entry = runner::find(day).ok_or(NotImplemented { day, part: None })?;
+    (entry.generate)(&mut Rng::new(seed), size)
+        .ok_or_else(|| Error::msg(format!("Day {} has no input generator", day)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::DAYS;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut rng = Rng::new(7);
+        let first: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        let mut rng = Rng::new(7);
+        let second: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+
+        assert_eq!(first, second);
+        assert_ne!(first[0], Rng::new(8).next_u64());
+    }
+
+    #[test]
+    fn test_between_stays_in_range() {
+        let mut rng = Rng::new(1);
+
+        assert!((0..1000).all(|_| (3..=5).contains(&rng.between(3, 5))));
+    }
+
+    #[test]
+    fn test_shuffle_keeps_items() {
+        let mut items: Vec<usize> = (0..20).collect();
+
+        Rng::new(3).shuffle(&mut items);
+
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_same_seed_generates_same_input() {
+        assert_eq!(generate(4, 5, 10).unwrap(), generate(4, 5, 10).unwrap());
+        assert_ne!(generate(4, 5, 10).unwrap(), generate(4, 6, 10).unwrap());
+    }
+
+    #[test]
+    fn test_every_day_solves_its_generated_inputs() {
+        for entry in DAYS {
+            for seed in 0..20 {
+                let input = generate(entry.day, seed, 30).unwrap();
+                let report = runner::run_input(entry, &input);
+
+                assert!(
+                    report.succeeded(),
+                    "Day {} seed {}: {}",
+                    entry.day,
+                    seed,
+                    report
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_returns_not_implemented_for_unknown_day() {
+        let error = generate(9999, 0, 10).unwrap_err();
+
+        assert!(error.is::<NotImplemented>());
+    }
+}
@@ -0,0 +1,425 @@
+use super::files::FileReader;
+use super::parse;
+use anyhow::{Context, Error, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+/// Most buckets shown in the line length histogram.
+const HISTOGRAM_BUCKETS: usize = 8;
+/// Width of the longest bar in the histogram.
+const HISTOGRAM_WIDTH: usize = 40;
+/// Most repeated line patterns shown.
+const PATTERNS: usize = 5;
+
+/// Quick facts about an input, for deciding how to parse it.
+#[derive(Debug, PartialEq)]
+pub struct Profile {
+    pub lines: usize,
+    pub blank_lines: usize,
+    pub line_endings: LineEndings,
+    /// How many lines end in spaces or tabs.
+    pub trailing_whitespace: usize,
+    /// How many lines each block between blank lines has.
+    pub blocks: Vec<usize>,
+    /// How many lines have each length, in characters.
+    pub line_lengths: BTreeMap<usize, usize>,
+    pub characters: BTreeSet<char>,
+    pub numbers: Option<Numbers>,
+    /// Line patterns that more than one line follows, most common first.
+    pub patterns: Vec<(String, usize)>,
+    /// Width and height, when every line is as long as the others.
+    pub grid: Option<(usize, usize)>,
+}
+
+/// How many lines end in `\n` alone and how many in `\r\n`.
+#[derive(Debug, PartialEq)]
+pub struct LineEndings {
+    pub lf: usize,
+    pub crlf: usize,
+}
+
+/// The integers found in an input.
+#[derive(Debug, PartialEq)]
+pub struct Numbers {
+    pub count: usize,
+    pub min: i64,
+    pub max: i64,
+}
+
+/// Reads `path` exactly as stored, keeping its line endings and trailing
+/// whitespace, and profiles it.
+pub fn inspect(file_reader: &dyn FileReader, path: &Path) -> Result<Profile> {
+    let bytes = file_reader
+        .read_bytes(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let input = String::from_utf8(bytes)
+        .map_err(|_| Error::msg(format!("{} is not valid UTF-8", path.display())))?;
+    Ok(profile(&input))
+}
+
+pub fn profile(input: &str) -> Profile {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut line_lengths = BTreeMap::new();
+    for line in &lines {
+        *line_lengths.entry(line.chars().count()).or_insert(0) += 1;
+    }
+    let crlf = input.matches("\r\n").count();
+    Profile {
+        lines: lines.len(),
+        blank_lines: lines.iter().filter(|line| line.trim().is_empty()).count(),
+        line_endings: LineEndings {
+            lf: input.matches('\n').count() - crlf,
+            crlf,
+        },
+        trailing_whitespace: lines
+            .iter()
+            .filter(|line| line.ends_with([' ', '\t']))
+            .count(),
+        blocks: parse::blocks(input)
+            .iter()
+            .map(|block| block.lines.len())
+            .collect(),
+        line_lengths,
+        characters: input
+            .chars()
+            .filter(|c| !matches!(c, '\n' | '\r'))
+            .collect(),
+        numbers: numbers(input),
+        patterns: patterns(&lines),
+        grid: grid(&lines),
+    }
+}
+
+fn numbers(input: &str) -> Option<Numbers> {
+    let numbers: Vec<i64> = parse::number_texts(input)
+        .iter()
+        .filter_map(|number| number.parse().ok())
+        .collect();
+    Some(Numbers {
+        count: numbers.len(),
+        min: *numbers.iter().min()?,
+        max: *numbers.iter().max()?,
+    })
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Number,
+    Word,
+    Other,
+}
+
+/// Splits a line into runs of digits, runs of letters and single other
+/// characters.
+fn tokens(line: &str) -> Vec<(Kind, &str)> {
+    let kind = |c: char| {
+        if c.is_ascii_digit() {
+            Kind::Number
+        } else if c.is_alphabetic() {
+            Kind::Word
+        } else {
+            Kind::Other
+        }
+    };
+    let mut tokens = Vec::new();
+    let mut current: Option<(Kind, usize)> = None;
+    for (index, c) in line.char_indices() {
+        match current {
+            Some((previous, _)) if previous == kind(c) && previous != Kind::Other => {}
+            Some((previous, start)) => {
+                tokens.push((previous, &line[start..index]));
+                current = Some((kind(c), index));
+            }
+            None => current = Some((kind(c), index)),
+        }
+    }
+    if let Some((previous, start)) = current {
+        tokens.push((previous, &line[start..]));
+    }
+    tokens
+}
+
+/// Groups lines that only differ in their numbers and words, and describes
+/// each group with `<n>` for a number and `<a>` for a word that varies.
+fn patterns(lines: &[&str]) -> Vec<(String, usize)> {
+    let mut groups: BTreeMap<Vec<String>, Vec<Vec<&str>>> = BTreeMap::new();
+    for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+        let tokens = tokens(line);
+        let shape = tokens
+            .iter()
+            .map(|(kind, text)| match kind {
+                Kind::Number => "<n>".to_string(),
+                Kind::Word => "<a>".to_string(),
+                Kind::Other => text.to_string(),
+            })
+            .collect();
+        let texts = tokens.iter().map(|(_, text)| *text).collect();
+        groups.entry(shape).or_default().push(texts);
+    }
+
+    let mut patterns: Vec<(String, usize)> = groups
+        .into_iter()
+        .filter(|(_, lines)| lines.len() > 1)
+        .map(|(shape, lines)| {
+            let pattern = shape
+                .iter()
+                .enumerate()
+                .map(|(index, placeholder)| {
+                    let first = lines[0][index];
+                    // Text that every line shares is shown as it is.
+                    if lines.iter().all(|line| line[index] == first) {
+                        first
+                    } else {
+                        placeholder.as_str()
+                    }
+                })
+                .collect();
+            (pattern, lines.len())
+        })
+        .collect();
+    patterns.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    patterns.truncate(PATTERNS);
+    patterns
+}
+
+fn grid(lines: &[&str]) -> Option<(usize, usize)> {
+    let width = lines.first()?.chars().count();
+    let is_grid = lines.len() > 1
+        && width > 1
+        && lines
+            .iter()
+            .all(|line| line.chars().count() == width && !line.chars().any(char::is_whitespace));
+    is_grid.then_some((width, lines.len()))
+}
+
+/// Describes a set of characters, collapsing runs like `'a'..='z'`.
+fn character_set(characters: &BTreeSet<char>) -> String {
+    let mut runs: Vec<(char, char)> = Vec::new();
+    for &c in characters {
+        match runs.last_mut() {
+            Some((_, end)) if char::from_u32(*end as u32 + 1) == Some(c) => *end = c,
+            _ => runs.push((c, c)),
+        }
+    }
+    let runs: Vec<String> = runs
+        .into_iter()
+        .flat_map(|(start, end)| match end as u32 - start as u32 {
+            0 => vec![format!("{:?}", start)],
+            1 => vec![format!("{:?}", start), format!("{:?}", end)],
+            _ => vec![format!("{:?}..={:?}", start, end)],
+        })
+        .collect();
+    runs.join(" ")
+}
+
+impl Profile {
+    /// Line lengths grouped into at most `HISTOGRAM_BUCKETS` equal ranges,
+    /// as the first and last length of each range and how many lines fall in
+    /// it.
+    pub fn histogram(&self) -> Vec<(usize, usize, usize)> {
+        let (Some((&shortest, _)), Some((&longest, _))) = (
+            self.line_lengths.first_key_value(),
+            self.line_lengths.last_key_value(),
+        ) else {
+            return Vec::new();
+        };
+        let width = (longest - shortest + 1).div_ceil(HISTOGRAM_BUCKETS);
+        (shortest..=longest)
+            .step_by(width)
+            .map(|start| {
+                let end = (start + width - 1).min(longest);
+                let count = self.line_lengths.range(start..=end).map(|(_, n)| n).sum();
+                (start, end, count)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Lines: {}, {} blank", self.lines, self.blank_lines)?;
+        match (self.blocks.iter().min(), self.blocks.iter().max()) {
+            (Some(min), Some(max)) if self.blocks.len() > 1 => writeln!(
+                f,
+                "Blocks: {} separated by blank lines, {} to {} lines each",
+                self.blocks.len(),
+                min,
+                max
+            )?,
+            _ => writeln!(f, "Blocks: {}", self.blocks.len())?,
+        }
+        match (self.line_endings.lf, self.line_endings.crlf) {
+            (0, 0) => writeln!(f, "Line endings: none")?,
+            (_, 0) => writeln!(f, "Line endings: LF")?,
+            (0, _) => writeln!(f, "Line endings: CRLF")?,
+            (lf, crlf) => writeln!(f, "Line endings: mixed, {} CRLF and {} LF", crlf, lf)?,
+        }
+        match self.trailing_whitespace {
+            0 => writeln!(f, "Trailing whitespace: none")?,
+            1 => writeln!(f, "Trailing whitespace: 1 line")?,
+            lines => writeln!(f, "Trailing whitespace: {} lines", lines)?,
+        }
+
+        writeln!(f, "Line lengths")?;
+        let histogram = self.histogram();
+        let most = histogram.iter().map(|(_, _, count)| *count).max();
+        for (start, end, count) in &histogram {
+            let range = if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            };
+            let bar = (count * HISTOGRAM_WIDTH).div_ceil(most.unwrap_or(1).max(1));
+            writeln!(
+                f,
+                "  {:>9} {:<width$} {}",
+                range,
+                "#".repeat(bar),
+                count,
+                width = HISTOGRAM_WIDTH
+            )?;
+        }
+
+        writeln!(
+            f,
+            "Characters: {} distinct: {}",
+            self.characters.len(),
+            character_set(&self.characters)
+        )?;
+        match &self.numbers {
+            Some(numbers) => writeln!(
+                f,
+                "Numbers: {} from {} to {}",
+                numbers.count, numbers.min, numbers.max
+            )?,
+            None => writeln!(f, "Numbers: none")?,
+        }
+        if self.patterns.is_empty() {
+            writeln!(f, "Repeated patterns: none")?;
+        } else {
+            writeln!(f, "Repeated patterns")?;
+            for (pattern, count) in &self.patterns {
+                writeln!(f, "  {:>6} x {}", count, pattern)?;
+            }
+        }
+        match self.grid {
+            Some((width, height)) => writeln!(f, "Grid: {} x {}", width, height),
+            None => writeln!(f, "Grid: no"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::InMemoryReader;
+
+    #[test]
+    fn test_counts_lines_and_blocks() {
+        let result = profile("1000\n2000\n\n4000\n\n5000\n6000\n7000\n");
+
+        assert_eq!((result.lines, result.blank_lines), (8, 2));
+        assert_eq!(result.blocks, vec![2, 1, 3]);
+        assert_eq!(result.line_lengths, BTreeMap::from([(0, 2), (4, 6)]));
+    }
+
+    #[test]
+    fn test_reports_line_endings_and_trailing_whitespace() {
+        let result = profile("    [D]    \r\n[N] [C]\r\n 1   2 \n");
+
+        assert_eq!(result.line_endings, LineEndings { lf: 1, crlf: 2 });
+        assert_eq!(result.trailing_whitespace, 2);
+        assert!(!result.characters.contains(&'\r'));
+        let text = result.to_string();
+        assert!(text.contains("Line endings: mixed, 2 CRLF and 1 LF"));
+        assert!(text.contains("Trailing whitespace: 2 lines"));
+        assert_eq!(profile("abc").line_endings, LineEndings { lf: 0, crlf: 0 });
+    }
+
+    #[test]
+    fn test_finds_number_range() {
+        let result = profile("2-4,6-80\nx=-3, y=12");
+
+        assert_eq!(
+            result.numbers,
+            Some(Numbers {
+                count: 6,
+                min: -3,
+                max: 80
+            })
+        );
+        assert_eq!(profile("abc").numbers, None);
+    }
+
+    #[test]
+    fn test_finds_repeated_patterns() {
+        let input = "    [D]\n[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1";
+
+        let result = profile(input);
+
+        assert_eq!(
+            result.patterns,
+            vec![("move <n> from <n> to <n>".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn test_orders_patterns_by_count() {
+        let result = profile("A Y\nB X\nC Z\nx=1\nx=2\nA Y");
+
+        assert_eq!(
+            result.patterns,
+            vec![("<a> <a>".to_string(), 4), ("x=<n>".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_detects_grid() {
+        assert_eq!(profile("30373\n25512\n65332\n").grid, Some((5, 3)));
+        assert_eq!(profile("abc\nabcd").grid, None);
+        assert_eq!(profile("a b\nc d").grid, None);
+        assert_eq!(profile("abcdef").grid, None);
+    }
+
+    #[test]
+    fn test_collapses_character_runs() {
+        let result = character_set(&"-,0123456789ab".chars().collect());
+
+        assert_eq!(result, "',' '-' '0'..='9' 'a' 'b'");
+    }
+
+    #[test]
+    fn test_buckets_line_lengths() {
+        let input: Vec<String> = (1..=20).map(|length| "x".repeat(length)).collect();
+
+        let result = profile(&input.join("\n")).histogram();
+
+        assert_eq!(result.len(), 7);
+        assert_eq!(result[0], (1, 3, 3));
+        assert_eq!(result[6], (19, 20, 2));
+    }
+
+    #[test]
+    fn test_inspects_file() {
+        let reader = InMemoryReader::from("mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+
+        let result = inspect(&reader, Path::new("some-file.txt")).unwrap();
+
+        assert_eq!(result.lines, 1);
+        assert!(result.to_string().contains("Characters: 18 distinct"));
+    }
+
+    #[test]
+    fn test_inspects_file_as_stored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input.txt");
+        std::fs::write(&path, "[Z] [M]    \r\n 1   2   3\r\n").unwrap();
+
+        let result = inspect(&crate::files::FileSystemReader, &path).unwrap();
+
+        assert_eq!(result.line_endings, LineEndings { lf: 0, crlf: 2 });
+        assert_eq!(result.trailing_whitespace, 1);
+        assert!(result.to_string().contains("Line endings: CRLF"));
+    }
+}
@@ -1,3 +1,10 @@
+pub mod answer;
+pub mod bench;
+pub mod cache;
+pub mod calendar;
+pub mod config;
+pub mod crash;
+pub mod crosscheck;
 pub mod day_0;
 pub mod day_1;
 pub mod day_2;
@@ -5,9 +12,156 @@ pub mod day_3;
 pub mod day_4;
 pub mod day_5;
 pub mod day_6;
+pub mod doctor;
+pub mod expected;
+pub mod external;
 pub mod files;
+pub mod generate;
+pub mod inspect;
+pub mod memory;
+pub mod minimize;
+pub mod parse;
+pub mod pattern;
+pub mod repl;
+pub mod report;
+pub mod runner;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod watch;
+pub use answer::Answer;
 use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
 
 pub trait Day<T> {
     fn run(&self) -> Result<T>;
 }
+
+/// A day's puzzle, split into parsing the input once and solving each part
+/// from the parsed input.
+pub trait Solution {
+    const DAY: usize;
+    /// Bump whenever the solution changes, so cached answers are recomputed.
+    const VERSION: u32;
+    /// The puzzle's title on adventofcode.com.
+    const TITLE: &'static str = "";
+    /// Tells this implementation apart from others for the same day.
+    const NAME: &'static str = "default";
+    /// The model built from the input. `Debug` and `Serialize` so it can be
+    /// inspected.
+    type Parsed: fmt::Debug + Serialize;
+
+    fn parse(input: &str) -> Result<Self::Parsed>;
+
+    fn part_1(_parsed: &Self::Parsed) -> Result<Answer> {
+        Err(NotImplemented {
+            day: Self::DAY,
+            part: Some(1),
+        }
+        .into())
+    }
+
+    fn part_2(_parsed: &Self::Parsed) -> Result<Answer> {
+        Err(NotImplemented {
+            day: Self::DAY,
+            part: Some(2),
+        }
+        .into())
+    }
+
+    fn part(part: usize, parsed: &Self::Parsed) -> Result<Answer> {
+        match part {
+            1 => Self::part_1(parsed),
+            2 => Self::part_2(parsed),
+            _ => Err(NotImplemented {
+                day: Self::DAY,
+                part: Some(part),
+            }
+            .into()),
+        }
+    }
+
+    /// Writes a random valid input with about `size` records, for days that
+    /// know how.
+    fn generate(_rng: &mut generate::Rng, _size: usize) -> Option<String> {
+        None
+    }
+
+    fn solve(part: usize, input: &str) -> Result<Answer> {
+        Self::part(part, &Self::parse(input)?)
+    }
+}
+
+/// Solves one part of a day's puzzle from its input text.
+pub fn solve(day: usize, part: usize, input: &str) -> Result<Answer> {
+    let entry = runner::find(day).ok_or(NotImplemented { day, part: None })?;
+    (entry.solve)(part, input)
+}
+
+/// Returned when asked for a day, or a part of a day, that has no solution.
+#[derive(Debug, PartialEq)]
+pub struct NotImplemented {
+    pub day: usize,
+    pub part: Option<usize>,
+}
+
+impl fmt::Display for NotImplemented {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.part {
+            Some(part) => write!(f, "Day {} part {} is not implemented yet", self.day, part),
+            None => write!(f, "Day {} is not implemented yet", self.day),
+        }
+    }
+}
+
+impl std::error::Error for NotImplemented {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solves_day_from_text() {
+        let result = solve(4, 1, "2-4,6-8\n2-8,3-7").unwrap();
+
+        assert_eq!(result, Answer::Number(1));
+    }
+
+    #[test]
+    fn test_days_can_be_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<day_1::Day1>();
+        assert_send_sync::<day_2::Day2>();
+        assert_send_sync::<day_3::Day3>();
+        assert_send_sync::<day_4::Day4>();
+        assert_send_sync::<day_5::Day5>();
+        assert_send_sync::<day_6::Day6>();
+    }
+
+    #[test]
+    fn test_returns_not_implemented_for_unknown_day() {
+        let error = solve(9999, 1, "").unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<NotImplemented>(),
+            Some(&NotImplemented {
+                day: 9999,
+                part: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_returns_not_implemented_for_missing_part() {
+        let error = solve(6, 2, "mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<NotImplemented>(),
+            Some(&NotImplemented {
+                day: 6,
+                part: Some(2)
+            })
+        );
+    }
+}
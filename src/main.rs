@@ -1,60 +1,900 @@
 use anyhow::{Context, Error, Result};
+use aoc_2022::cache::{self, Cache};
+use aoc_2022::config::{self, Config};
+use aoc_2022::external::External;
+use aoc_2022::files::{FileReader, FileSystemReader};
+use aoc_2022::runner::{self, Entry, Solver};
 use aoc_2022::*;
-use clap::Parser;
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "mem")]
+#[global_allocator]
+static GLOBAL: memory::CountingAllocator = memory::CountingAllocator;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Time each day's phases over many runs, saving or comparing baselines.
+    Bench(BenchArgs),
+    /// Show a grid of the 25 days with each part's progress and the best
+    /// runtime.
+    Calendar {
+        /// How many times to run each implementation when looking for the
+        /// best runtime.
+        #[arg(long, default_value_t = 3)]
+        runs: usize,
+    },
+    /// Manage the cache of answers.
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Check that inputs, stored answers, the config and the session token
+    /// are in order.
+    Doctor {
+        /// Path to the config file.
+        #[arg(long, default_value = config::DEFAULT_PATH)]
+        config: PathBuf,
+        /// Record the checksums of the current inputs before checking.
+        #[arg(long)]
+        record: bool,
+    },
+    /// Write a random but valid input for a day.
+    Gen {
+        /// The day to generate an input for.
+        day: usize,
+        /// Inputs generated from the same seed are identical.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Roughly how many records (elves, rounds, lines or moves) to write.
+        #[arg(long, default_value_t = 1000)]
+        size: usize,
+        /// Write to this file instead of printing.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Run every implementation of each day on the same inputs and report
+    /// where they disagree.
+    Crosscheck {
+        /// A day number, or `all` to check every day.
+        #[arg(default_value = "all")]
+        day: Selection,
+        /// How many generated inputs to check besides the puzzle input and
+        /// examples.
+        #[arg(long, default_value_t = 20)]
+        generated: usize,
+        /// Seed of the first generated input.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Size of the generated inputs.
+        #[arg(long, default_value_t = 100)]
+        size: usize,
+        /// Path to the config file listing external solvers.
+        #[arg(long, default_value = config::DEFAULT_PATH)]
+        config: PathBuf,
+    },
+    /// Summarise the shape of an input file before writing its parser.
+    Inspect {
+        /// The file to inspect.
+        path: PathBuf,
+    },
+    /// Shrink an input to the smallest one that still fails the same way.
+    Minimize {
+        /// The day the input is for.
+        day: usize,
+        /// The failing input.
+        #[arg(long)]
+        input: PathBuf,
+        /// The failure to keep: `panic`, `error`, or `mismatch` between
+        /// implementations.
+        #[arg(long)]
+        until: minimize::Failure,
+        /// Run the day's implementation with this name when looking for panics
+        /// or errors.
+        #[arg(long = "impl", value_name = "NAME")]
+        implementation: Option<String>,
+        /// Where to write the smallest input, next to the input by default.
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Path to the config file listing external solvers.
+        #[arg(long, default_value = config::DEFAULT_PATH)]
+        config: PathBuf,
+    },
+    /// Explore days interactively, switching inputs and inspecting parses.
+    Repl,
+    /// List each day's progress, verification and timings from the last
+    /// benchmark.
+    Report {
+        /// Write the report as a Markdown table.
+        #[arg(long)]
+        markdown: bool,
+        /// Replace the marked report section of this file instead of
+        /// printing.
+        #[arg(long, value_name = "PATH", requires = "markdown")]
+        update: Option<PathBuf>,
+        /// Directory the baselines are kept in.
+        #[arg(long, default_value = bench::BASELINE_DIR)]
+        baseline_dir: PathBuf,
+    },
+    /// Check every implementation, external solvers included, against the
+    /// answers stored next to each day's input and examples.
+    Verify {
+        /// A day number, or `all` to verify every day.
+        #[arg(default_value = "all")]
+        day: Selection,
+        /// Only verify the implementation with this name.
+        #[arg(long = "impl", value_name = "NAME")]
+        implementation: Option<String>,
+        /// Path to the config file listing external solvers.
+        #[arg(long, default_value = config::DEFAULT_PATH)]
+        config: PathBuf,
+    },
+    /// Re-run a day whenever its assets or input change.
+    Watch {
+        /// The day to run.
+        day: usize,
+        /// Read the input from this file instead of `assets/day_N/input.txt`.
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Remove every cached answer.
+    Clear {
+        /// Directory the answers are cached in.
+        #[arg(long, default_value = cache::DEFAULT_DIR)]
+        cache_dir: PathBuf,
+    },
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// A day number, or `all` to run every implemented day.
+    #[arg(required = true)]
+    day: Option<Selection>,
+    /// Read the input from this file instead of `assets/day_N/input.txt`.
+    #[arg(long, conflicts_with = "example")]
+    input: Option<PathBuf>,
+    /// Run against `assets/day_N/example_<n>.txt` instead, checking the
+    /// answers stored next to it.
+    #[arg(long, num_args = 0..=1, default_missing_value = "1")]
+    example: Option<usize>,
+    /// Run the day's implementation with this name instead of the registered
+    /// one.
+    #[arg(long = "impl", value_name = "NAME")]
+    implementation: Option<String>,
+    /// Seconds each day may run before it is abandoned, overriding the config.
+    #[arg(long, value_parser = config::parse_timeout)]
+    timeout: Option<Duration>,
+    /// Path to the config file.
+    #[arg(long, default_value = config::DEFAULT_PATH)]
+    config: PathBuf,
+    /// Where each day runs, so a crash only takes down that day.
+    #[arg(long, value_enum, default_value_t = Isolation::Thread)]
+    isolation: Isolation,
+    /// Report heap usage for each phase, solving every day again since cached
+    /// answers carry none. Needs the `mem` feature.
+    #[arg(long)]
+    mem: bool,
+    /// How many days to run at the same time.
+    #[arg(long, short, default_value_t = 1)]
+    jobs: usize,
+    /// Print each day's parsed input as JSON instead of solving it.
+    #[arg(long)]
+    dump_parsed: bool,
+    /// Solve every day even if its answers are cached.
+    #[arg(long)]
+    no_cache: bool,
+    /// Directory the answers are cached in.
+    #[arg(long, default_value = cache::DEFAULT_DIR)]
+    cache_dir: PathBuf,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// A day number, or `all` to benchmark every implemented day.
+    #[arg(default_value = "all")]
+    day: Selection,
+    /// Benchmark the day's implementation with this name.
+    #[arg(long = "impl", value_name = "NAME")]
+    implementation: Option<String>,
+    /// How many times to run each day.
+    #[arg(
+        long,
+        default_value_t = 30,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    samples: usize,
+    /// Save the results as a baseline with this name.
+    #[arg(long)]
+    save: Option<String>,
+    /// Compare the results with the baseline with this name.
+    #[arg(long)]
+    compare: Option<String>,
+    /// Percentage slowdown against the baseline that counts as a regression.
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+    /// Directory the baselines are kept in.
+    #[arg(long, default_value = bench::BASELINE_DIR)]
+    baseline_dir: PathBuf,
+    /// Path to the config file listing external solvers.
+    #[arg(long, default_value = config::DEFAULT_PATH)]
+    config: PathBuf,
+}
+
+#[derive(Clone, ValueEnum)]
+enum Isolation {
+    /// Catch panics in this process.
+    Thread,
+    /// Run each day in its own child process.
+    Process,
+}
+
+#[derive(Clone)]
+enum Selection {
+    One(usize),
+    All,
+}
+
+impl FromStr for Selection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "all" {
+            Ok(Selection::All)
+        } else {
+            Ok(Selection::One(s.parse()?))
+        }
+    }
+}
+
+impl Selection {
+    fn entries(&self) -> Result<Vec<&'static Entry>> {
+        match self {
+            Selection::One(day_number) => {
+                Ok(vec![runner::find(*day_number).ok_or(NotImplemented {
+                    day: *day_number,
+                    part: None,
+                })?])
+            }
+            Selection::All => Ok(runner::DAYS.iter().collect()),
+        }
+    }
+
+    /// The selected days, using the implementation called `name`, built in
+    /// or external, if given.
+    fn implementations<'a>(
+        &self,
+        name: Option<&str>,
+        config: &'a Config,
+    ) -> Result<Vec<Implementation<'a>>> {
+        match (self, name) {
+            (Selection::One(day_number), Some(name)) => {
+                Ok(vec![find_implementation(*day_number, config, name)?])
+            }
+            (Selection::All, Some(_)) => Err(Error::msg(
+                "An implementation can only be picked for a single day",
+            )),
+            (_, None) => Ok(self
+                .entries()?
+                .into_iter()
+                .map(Implementation::Registered)
+                .collect()),
+        }
+    }
+}
+
+/// A day's implementation, either registered in the runner or an external
+/// solver from the config.
+#[derive(Clone, Copy)]
+enum Implementation<'a> {
+    Registered(&'static Entry),
+    External(usize, &'a External),
+}
+
+impl<'a> Implementation<'a> {
+    fn day(self) -> usize {
+        match self {
+            Implementation::Registered(entry) => entry.day,
+            Implementation::External(day, _) => day,
+        }
+    }
+
+    fn solver(self) -> &'a dyn Solver {
+        match self {
+            Implementation::Registered(entry) => entry,
+            Implementation::External(_, external) => external,
+        }
+    }
+}
+
+/// Every implementation of `day`, the registered one first and then the
+/// external solvers from the config.
+fn implementations(day: usize, config: &Config) -> Vec<Implementation<'_>> {
+    let registered = runner::implementations(day)
+        .into_iter()
+        .map(Implementation::Registered);
+    let external = config
+        .solvers(day)
+        .into_iter()
+        .map(|external| Implementation::External(day, external));
+    registered.chain(external).collect()
+}
+
+/// Finds the implementation of `day` called `name`, built in or external.
+fn find_implementation<'a>(
     day: usize,
+    config: &'a Config,
+    name: &str,
+) -> Result<Implementation<'a>> {
+    runner::find_named(day, name, implementations(day, config), |implementation| {
+        implementation.solver().name()
+    })
+}
+
+/// Every implementation of `day` as a [`Solver`].
+fn solvers(day: usize, config: &Config) -> Vec<&dyn Solver> {
+    implementations(day, config)
+        .into_iter()
+        .map(Implementation::solver)
+        .collect()
+}
+
+/// Finds the [`Solver`] of `day` called `name`.
+fn find_solver<'a>(day: usize, config: &'a Config, name: &str) -> Result<&'a dyn Solver> {
+    Ok(find_implementation(day, config, name)?.solver())
 }
 
 fn main() -> Result<()> {
     let args = Cli::parse();
-    run(args.day).with_context(|| "Failed to run.")?;
-    Ok(())
-}
-
-fn run(day_number: usize) -> Result<()> {
-    match day_number {
-        0 => day_0::Day0 {}
-            .run()
-            .with_context(|| "Failed to run day 0.")?,
-        1 => {
-            let result = day_1::Day1::default()
-                .run()
-                .with_context(|| "Failed to run day 1.")?;
-            println!("Max calories: {}", result);
-        }
-        2 => {
-            day_2::Day2::default()
-                .run()
-                .with_context(|| "Failed to run day 2.")?;
-        }
-        3 => {
-            day_3::Day3::default()
-                .run()
-                .with_context(|| "Failed to run day 3.")?;
-        }
-        4 => {
-            day_4::Day4::default()
-                .run()
-                .with_context(|| "Failed to run day 4.")?;
-        }
-        5 => {
-            day_5::Day5::default()
-                .run()
-                .with_context(|| "Failed to run day 5.")?;
-        }
-        6 => {
-            day_6::Day6::default()
-                .run()
-                .with_context(|| "Failed to run day 6.")?;
-        }
-        _ => {
-            return Err(Error::msg(format!(
-                "Day {} is not implemented yet",
-                day_number
-            )))
+    match args.command {
+        Some(Commands::Bench(bench_args)) => {
+            bench(bench_args).with_context(|| "Failed to benchmark.")?
+        }
+        Some(Commands::Cache {
+            action: CacheAction::Clear { cache_dir },
+        }) => {
+            Cache::new(&cache_dir)
+                .clear()
+                .with_context(|| "Failed to clear the cache.")?;
+            println!("Cleared {}", cache_dir.display());
+        }
+        Some(Commands::Calendar { runs }) => {
+            print!("{}", calendar::calendar(&FileSystemReader {}, runs))
+        }
+        Some(Commands::Crosscheck {
+            day,
+            generated,
+            seed,
+            size,
+            config,
+        }) => crosscheck(day, generated, seed, size, &Config::load(&config)?)
+            .with_context(|| "Failed to crosscheck.")?,
+        Some(Commands::Doctor { config, record }) => {
+            doctor(config, record).with_context(|| "Failed health check.")?
+        }
+        Some(Commands::Gen {
+            day,
+            seed,
+            size,
+            output,
+        }) => {
+            let input = generate::generate(day, seed, size)
+                .with_context(|| format!("Failed to generate an input for day {}.", day))?;
+            match output {
+                Some(path) => std::fs::write(&path, input + "\n")
+                    .with_context(|| format!("Failed to write {}", path.display()))?,
+                None => println!("{}", input),
+            }
+        }
+        Some(Commands::Inspect { path }) => {
+            print!("{}", inspect::inspect(&FileSystemReader {}, &path)?)
+        }
+        Some(Commands::Minimize {
+            day,
+            input,
+            until,
+            implementation,
+            output,
+            config,
+        }) => minimize(
+            day,
+            input,
+            until,
+            implementation,
+            output,
+            &Config::load(&config)?,
+        )
+        .with_context(|| format!("Failed to minimize the input for day {}.", day))?,
+        Some(Commands::Report {
+            markdown,
+            update,
+            baseline_dir,
+        }) => report(markdown, update, baseline_dir).with_context(|| "Failed to report.")?,
+        Some(Commands::Repl) => {
+            let stdin = std::io::stdin();
+            repl::Repl::new(Box::new(FileSystemReader {})).run(stdin.lock(), std::io::stdout())?
         }
+        Some(Commands::Verify {
+            day,
+            implementation,
+            config,
+        }) => verify(day, implementation, &Config::load(&config)?)
+            .with_context(|| "Failed to verify.")?,
+        Some(Commands::Watch { day, input }) => {
+            watch(day, input).with_context(|| format!("Failed to watch day {}.", day))?
+        }
+        None => run(args.run).with_context(|| "Failed to run.")?,
+    }
+    Ok(())
+}
+
+fn run(args: RunArgs) -> Result<()> {
+    let config = Config::load(&args.config)?;
+    let entries = match &args.day {
+        Some(Selection::One(0)) => {
+            return day_0::Day0 {}.run().with_context(|| "Failed to run day 0.")
+        }
+        Some(selection) => selection.implementations(args.implementation.as_deref(), &config)?,
+        None => unreachable!("clap requires a day without a subcommand"),
+    };
+    if args.input.is_some() && entries.len() > 1 {
+        return Err(Error::msg(
+            "An input file can only be given for a single day",
+        ));
+    }
+    if args.mem {
+        if !cfg!(feature = "mem") {
+            return Err(Error::msg(
+                "Memory accounting needs the `mem` feature, run with `--features mem`",
+            ));
+        }
+        memory::enable();
+    }
+    let file_reader: Arc<dyn FileReader> = Arc::new(FileSystemReader {});
+    let executable = env::current_exe()?;
+    let cache = (!args.no_cache && !args.mem).then(|| Cache::new(&args.cache_dir));
+
+    let input_path = |day: usize| match (&args.input, args.example) {
+        (Some(path), _) => path.clone(),
+        (None, Some(n)) => expected::example_path(day, n),
+        (None, None) => runner::input_path(day),
+    };
+    if args.dump_parsed {
+        let entries = entries
+            .iter()
+            .map(|implementation| match implementation {
+                Implementation::Registered(entry) => Ok(*entry),
+                Implementation::External(_, external) => Err(Error::msg(format!(
+                    "Solver {} is external, so there is no parsed input to dump",
+                    external.name
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return dump_parsed(&entries, file_reader.as_ref(), input_path);
+    }
+    let run_entry = |implementation: Implementation| {
+        let day = implementation.day();
+        let path = input_path(day);
+        let limit = args.timeout.or_else(|| config.timeout(day));
+        match (&args.isolation, limit) {
+            (Isolation::Process, limit) => {
+                let mut command = Command::new(&executable);
+                command
+                    .arg(day.to_string())
+                    .arg("--config")
+                    .arg(&args.config);
+                if args.mem {
+                    command.arg("--mem");
+                }
+                if args.no_cache {
+                    command.arg("--no-cache");
+                }
+                command.arg("--cache-dir").arg(&args.cache_dir);
+                command.arg("--input").arg(&path);
+                command.arg("--impl").arg(implementation.solver().name());
+                return runner::run_day_in_process(day, command, limit);
+            }
+            (Isolation::Thread, _) => {}
+        }
+        let entry = match implementation {
+            Implementation::Registered(entry) => entry,
+            // External solvers keep no version to cache their answers under.
+            Implementation::External(day, external) => {
+                return match limit {
+                    Some(limit) => runner::run_solver(
+                        &external.with_limit(limit),
+                        day,
+                        file_reader.as_ref(),
+                        &path,
+                    ),
+                    None => runner::run_solver(external, day, file_reader.as_ref(), &path),
+                };
+            }
+        };
+
+        let start = Instant::now();
+        let input = cache
+            .as_ref()
+            .and_then(|_| file_reader.read_to_string(&path).ok());
+        if let (Some(cache), Some(input)) = (&cache, &input) {
+            if let Some(mut report) = cache.lookup(entry, input) {
+                report.wall = start.elapsed();
+                return report;
+            }
+        }
+        let report = match limit {
+            Some(limit) => runner::run_day_with_limit(entry, file_reader.clone(), path, limit),
+            None => runner::run_day(entry, file_reader.as_ref(), &path),
+        };
+        if let (Some(cache), Some(input)) = (&cache, &input) {
+            if let Err(error) = cache.store(entry, input, &report) {
+                eprintln!("Failed to cache day {}: {:#}", entry.day, error);
+            }
+        }
+        report
+    };
+
+    let mut reports = Vec::new();
+    let mut wrong = Vec::new();
+    runner::run_parallel(&entries, args.jobs, run_entry, |report| {
+        match &report.output {
+            Some(output) => print!("{}", output),
+            None => print!("{}", report),
+        }
+        if report.output.is_some() && !report.succeeded() {
+            println!("Day {}\n  {}", report.day, report.status);
+        }
+        // Child processes check their own answers, and days that didn't
+        // finish have already been reported as failed.
+        if report.output.is_none() && matches!(report.status, runner::Status::Completed) {
+            let path = input_path(report.day);
+            match expected::load(&path) {
+                Ok(Some(expected)) => {
+                    let mismatches = expected::check(&report, &expected);
+                    for mismatch in &mismatches {
+                        println!("  {}", mismatch);
+                    }
+                    if mismatches.is_empty() {
+                        println!(
+                            "  Answers match {}",
+                            expected::answers_path(&path).display()
+                        );
+                    } else {
+                        wrong.push(report.day);
+                    }
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    println!("  {:#}", error);
+                    wrong.push(report.day);
+                }
+            }
+        }
+        reports.push(report);
+    });
+
+    if reports.len() > 1 {
+        println!("Summary");
+        for report in &reports {
+            println!(
+                "  Day {}: {} ({:?})",
+                report.day, report.status, report.wall
+            );
+        }
+    }
+
+    let failed: Vec<usize> = reports
+        .iter()
+        .filter(|report| !report.succeeded())
+        .map(|report| report.day)
+        .collect();
+    if !failed.is_empty() {
+        return Err(Error::msg(format!("Failed to run days {:?}.", failed)));
+    }
+    if !wrong.is_empty() {
+        return Err(Error::msg(format!("Wrong answers for days {:?}.", wrong)));
+    }
+    Ok(())
+}
+
+/// Prints the parsed model of one day, or of several keyed by day number.
+fn dump_parsed(
+    entries: &[&Entry],
+    file_reader: &dyn FileReader,
+    input_path: impl Fn(usize) -> PathBuf,
+) -> Result<()> {
+    let mut models = serde_json::Map::new();
+    for entry in entries {
+        let path = input_path(entry.day);
+        let input = file_reader.read_to_string(&path)?;
+        let model = (entry.dump)(&input).with_context(|| {
+            format!("Failed to parse day {} from {}", entry.day, path.display())
+        })?;
+        models.insert(entry.day.to_string(), model);
+    }
+    let output = match (entries.len(), models.values().next()) {
+        (1, Some(model)) => model.clone(),
+        _ => serde_json::Value::Object(models),
+    };
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+fn doctor(config: PathBuf, record: bool) -> Result<()> {
+    let file_reader = FileSystemReader {};
+    let settings = doctor::Settings {
+        config,
+        manifest: PathBuf::from(doctor::MANIFEST_PATH),
+        session: env::var(doctor::SESSION_VAR).ok(),
+        session_path: PathBuf::from(doctor::SESSION_PATH),
+    };
+    if record {
+        let manifest = doctor::Manifest::record(&file_reader);
+        manifest.save(&settings.manifest)?;
+        println!(
+            "Recorded checksums of {} inputs in {}",
+            manifest.inputs.len(),
+            settings.manifest.display()
+        );
+    }
+    let report = doctor::diagnose(&file_reader, &settings);
+    print!("{}", report);
+    if report.errors() > 0 {
+        return Err(Error::msg(format!("Found {} problems.", report.errors())));
+    }
+    Ok(())
+}
+
+fn report(markdown: bool, update: Option<PathBuf>, baseline_dir: PathBuf) -> Result<()> {
+    let benchmark = bench::load(&baseline_dir, bench::LAST).unwrap_or_else(|_| {
+        eprintln!("No benchmark recorded yet, run `bench` to include timings.");
+        Vec::new()
+    });
+    let report = report::report(&FileSystemReader {}, &benchmark);
+    match update {
+        Some(path) => {
+            let document = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let document = report::replace_section(&document, &report.markdown())
+                .with_context(|| format!("Failed to find the report in {}", path.display()))?;
+            std::fs::write(&path, document)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("Updated the report in {}", path.display());
+        }
+        None if markdown => print!("{}", report.markdown()),
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+fn bench(args: BenchArgs) -> Result<()> {
+    let dir = args.baseline_dir.as_path();
+    let baseline = match &args.compare {
+        Some(name) => Some(bench::load(dir, name)?),
+        None => None,
+    };
+
+    let config = Config::load(&args.config)?;
+    let entries = args.day.entries()?;
+    if entries.len() > 1 && args.implementation.is_some() {
+        return Err(Error::msg(
+            "An implementation can only be picked for a single day",
+        ));
+    }
+    let mut results = Vec::new();
+    for entry in entries {
+        let solver = match &args.implementation {
+            Some(name) => find_solver(entry.day, &config, name)?,
+            None => entry,
+        };
+        let input = FileSystemReader {}.read_to_string(&entry.input_path())?;
+        let statistics = bench::benchmark(entry.day, solver, &input, args.samples)
+            .with_context(|| format!("Failed to benchmark day {}", entry.day))?;
+        if baseline.is_none() {
+            for phase in &statistics {
+                println!("{}", phase);
+            }
+        }
+        results.extend(statistics);
+    }
+
+    if args.implementation.is_none() {
+        bench::record_last(dir, &results)?;
+    }
+    if let Some(name) = &args.save {
+        bench::save(dir, name, &results)?;
+        println!("Saved baseline {}", name);
+    }
+    if let Some(baseline) = baseline {
+        let comparisons = bench::compare(&baseline, &results);
+        for comparison in &comparisons {
+            println!("{}", comparison);
+        }
+        bench::check_regressions(&comparisons, args.threshold / 100.0)?;
+    }
+    Ok(())
+}
+
+fn watch(day: usize, input: Option<PathBuf>) -> Result<()> {
+    let entry = runner::find(day).ok_or(NotImplemented { day, part: None })?;
+    let path = input.unwrap_or_else(|| entry.input_path());
+    let mut paths = vec![PathBuf::from(format!("assets/day_{}", day))];
+    if !path.starts_with(&paths[0]) {
+        paths.push(path.clone());
+    }
+    let mut watcher = watch::Watcher::new(paths);
+    let mut previous: Option<Vec<(usize, String)>> = None;
+    loop {
+        if previous.is_none() || watcher.changed() {
+            let report = runner::run_day(entry, &FileSystemReader {}, &path);
+            let answers = watch::answers(&report);
+            print!("\x1b[2J\x1b[H{}", report);
+            if let Some(previous) = &previous {
+                println!("Changes");
+                for line in watch::diff(previous, &answers) {
+                    println!("  {}", line);
+                }
+            }
+            println!(
+                "Watching {} for changes, press Ctrl-C to stop.",
+                path.display()
+            );
+            previous = Some(answers);
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+}
+
+fn crosscheck(
+    selection: Selection,
+    generated: usize,
+    seed: u64,
+    size: usize,
+    config: &Config,
+) -> Result<()> {
+    let mut disagreeing = Vec::new();
+    for entry in selection.entries()? {
+        let implementations = solvers(entry.day, config);
+        let names: Vec<&str> = implementations.iter().map(|solver| solver.name()).collect();
+        if implementations.len() < 2 {
+            println!("Day {}: only one implementation", entry.day);
+            continue;
+        }
+        let inputs = crosscheck::inputs(entry.day, &FileSystemReader {}, seed, generated, size)?;
+        let disagreements = crosscheck::crosscheck(entry.day, &implementations, &inputs);
+        if disagreements.is_empty() {
+            println!(
+                "Day {}: {} agree on {} inputs",
+                entry.day,
+                names.join(", "),
+                inputs.len()
+            );
+        } else {
+            for disagreement in &disagreements {
+                print!("{}", disagreement);
+            }
+            disagreeing.push(entry.day);
+        }
+    }
+    if !disagreeing.is_empty() {
+        return Err(Error::msg(format!(
+            "Implementations disagree for days {:?}.",
+            disagreeing
+        )));
+    }
+    Ok(())
+}
+
+fn minimize(
+    day: usize,
+    input: PathBuf,
+    until: minimize::Failure,
+    implementation: Option<String>,
+    output: Option<PathBuf>,
+    config: &Config,
+) -> Result<()> {
+    let mut implementations = solvers(day, config);
+    if let Some(name) = implementation {
+        let chosen = find_solver(day, config, &name)?;
+        implementations.retain(|solver| solver.name() != chosen.name());
+        implementations.insert(0, chosen);
+    }
+    if implementations.is_empty() {
+        return Err(NotImplemented { day, part: None }.into());
+    }
+    let text = FileSystemReader {}.read_to_string(&input)?;
+    if !minimize::reproduces(until, day, &implementations, &text) {
+        return Err(Error::msg(format!(
+            "{} does not fail with {}",
+            input.display(),
+            until
+        )));
+    }
+
+    let smallest = minimize::minimize(&text, |text| {
+        minimize::reproduces(until, day, &implementations, text)
+    });
+    let output = output.unwrap_or_else(|| input.with_extension("min.txt"));
+    std::fs::write(&output, smallest.clone() + "\n")
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    println!(
+        "Reduced {} lines to {}, wrote {}",
+        text.lines().count(),
+        smallest.lines().count(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn verify(selection: Selection, implementation: Option<String>, config: &Config) -> Result<()> {
+    let entries = selection.entries()?;
+    if entries.len() > 1 && implementation.is_some() {
+        return Err(Error::msg(
+            "An implementation can only be picked for a single day",
+        ));
+    }
+    let mut wrong = Vec::new();
+    for entry in entries {
+        let mut inputs = Vec::new();
+        let mut paths = vec![entry.input_path()];
+        paths.extend(expected::examples(entry.day)?);
+        for path in paths {
+            if let Some(expected) = expected::load(&path)? {
+                let text = FileSystemReader {}.read_to_string(&path)?;
+                inputs.push((path, text, expected));
+            }
+        }
+        let implementations = match &implementation {
+            Some(name) => vec![find_solver(entry.day, config, name)?],
+            None => solvers(entry.day, config),
+        };
+
+        println!("Day {}", entry.day);
+        for solver in implementations {
+            let mut mismatches = Vec::new();
+            for (path, text, expected) in &inputs {
+                let report = solver.run_input(entry.day, text);
+                for mismatch in expected::check(&report, expected) {
+                    mismatches.push(format!("{}: {}", path.display(), mismatch));
+                }
+            }
+            if mismatches.is_empty() {
+                println!(
+                    "  {}: answers match on {} inputs",
+                    solver.name(),
+                    inputs.len()
+                );
+            } else {
+                println!("  {}: wrong answers", solver.name());
+                for mismatch in &mismatches {
+                    println!("    {}", mismatch);
+                }
+                wrong.push(entry.day);
+            }
+        }
+    }
+    if !wrong.is_empty() {
+        wrong.dedup();
+        return Err(Error::msg(format!("Wrong answers for days {:?}.", wrong)));
     }
     Ok(())
 }
@@ -0,0 +1,205 @@
+//! Heap accounting for the runner's `--mem` flag.
+//!
+//! Counting only happens once [`CountingAllocator`] is registered as the
+//! global allocator, which the binary does when built with the `mem` feature.
+//! Counters are kept per thread, so days running on other threads don't skew
+//! each other's numbers.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Wraps the system allocator, counting every allocation made through it.
+pub struct CountingAllocator;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy)]
+struct Counters {
+    current: isize,
+    peak: isize,
+    allocations: usize,
+    bytes: usize,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            current: 0,
+            peak: 0,
+            allocations: 0,
+            bytes: 0,
+        })
+    };
+}
+
+fn record(allocated: usize, freed: usize) {
+    // The counters may already be gone while a thread is shutting down.
+    let _ = COUNTERS.try_with(|counters| {
+        let mut value = counters.get();
+        value.current += allocated as isize - freed as isize;
+        value.peak = value.peak.max(value.current);
+        if allocated > 0 {
+            value.allocations += 1;
+            value.bytes += allocated;
+        }
+        counters.set(value);
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ACTIVE.store(true, Ordering::Relaxed);
+        record(layout.size(), 0);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ACTIVE.store(true, Ordering::Relaxed);
+        record(layout.size(), 0);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(0, layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size, layout.size());
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Heap usage of a single phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemoryUsage {
+    /// Most bytes held at once, above what was held when the phase started.
+    pub peak: usize,
+    pub allocations: usize,
+    pub bytes: usize,
+}
+
+/// Turns on measuring in [`measure`].
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// True when measurements are wanted and the counting allocator is in use.
+pub fn is_counting() -> bool {
+    ENABLED.load(Ordering::Relaxed) && ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, returning the heap usage on this thread while it ran if
+/// [`is_counting`].
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<MemoryUsage>) {
+    if !is_counting() {
+        return (f(), None);
+    }
+
+    let before = COUNTERS.with(|counters| {
+        let mut value = counters.get();
+        let before = value;
+        value.peak = value.current;
+        counters.set(value);
+        before
+    });
+    let result = f();
+    let after = COUNTERS.with(|counters| {
+        let mut value = counters.get();
+        let after = value;
+        value.peak = value.peak.max(before.peak);
+        counters.set(value);
+        after
+    });
+
+    let usage = MemoryUsage {
+        peak: (after.peak - before.current).max(0) as usize,
+        allocations: after.allocations - before.allocations,
+        bytes: after.bytes - before.bytes,
+    };
+    (result, Some(usage))
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "peak {}, {} allocations, {} allocated",
+            Bytes(self.peak),
+            self.allocations,
+            Bytes(self.bytes)
+        )
+    }
+}
+
+struct Bytes(usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+        let mut size = self.0 as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        if unit == 0 {
+            write!(f, "{} B", self.0)
+        } else {
+            write!(f, "{:.1} {}", size, UNITS[unit])
+        }
+    }
+}
+
+#[cfg(test)]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measures_allocations_on_this_thread() {
+        enable();
+
+        let (buffer, usage) = measure(|| vec![0u8; 4096]);
+
+        let usage = usage.unwrap();
+        assert_eq!(buffer.len(), 4096);
+        assert!(usage.allocations >= 1);
+        assert!(usage.bytes >= 4096);
+        assert!(usage.peak >= 4096);
+    }
+
+    #[test]
+    fn test_peak_only_counts_memory_held_at_once() {
+        enable();
+
+        let (_, usage) = measure(|| {
+            for _ in 0..10 {
+                drop(vec![0u8; 1024]);
+            }
+        });
+
+        let usage = usage.unwrap();
+        assert!(usage.bytes >= 10 * 1024);
+        assert!(usage.peak < 2 * 1024);
+    }
+
+    #[test]
+    fn test_formats_sizes() {
+        let usage = MemoryUsage {
+            peak: 512,
+            allocations: 3,
+            bytes: 3 * 1024 * 1024,
+        };
+
+        assert_eq!(
+            usage.to_string(),
+            "peak 512 B, 3 allocations, 3.0 MiB allocated"
+        );
+    }
+}
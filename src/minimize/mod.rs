@@ -0,0 +1,265 @@
+use super::crash::Crash;
+use super::crosscheck::{self, Input};
+use super::runner::{DayReport, Solver, Status};
+use anyhow::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// The kind of failure to keep while shrinking an input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Failure {
+    /// Parsing or a part panics.
+    Panic,
+    /// Parsing or a part panics or returns an error.
+    Error,
+    /// Implementations of the day give different answers.
+    Mismatch,
+}
+
+impl FromStr for Failure {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "panic" => Ok(Failure::Panic),
+            "error" => Ok(Failure::Error),
+            "mismatch" => Ok(Failure::Mismatch),
+            _ => Err(Error::msg(format!(
+                "Unknown failure {}, expected panic, error or mismatch",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Panic => write!(f, "panic"),
+            Failure::Error => write!(f, "error"),
+            Failure::Mismatch => write!(f, "mismatch"),
+        }
+    }
+}
+
+/// True if running the implementations of `day` on `input` fails in the way
+/// asked for. Panics and errors come from the first implementation only.
+pub fn reproduces(
+    failure: Failure,
+    day: usize,
+    implementations: &[&dyn Solver],
+    input: &str,
+) -> bool {
+    match failure {
+        Failure::Panic => crashed(&implementations[0].run_input(day, input)),
+        Failure::Error => !implementations[0].run_input(day, input).succeeded(),
+        Failure::Mismatch => {
+            let input = Input {
+                label: String::new(),
+                text: input.to_string(),
+            };
+            !crosscheck::crosscheck(day, implementations, &[input]).is_empty()
+        }
+    }
+}
+
+fn crashed(report: &DayReport) -> bool {
+    let status = match &report.status {
+        Status::Failed(error) => error.is::<Crash>(),
+        _ => false,
+    };
+    status
+        || report
+            .parts
+            .iter()
+            .any(|part| matches!(&part.answer, Err(error) if error.is::<Crash>()))
+}
+
+/// Shrinks `input` while `fails` still holds, first dropping blocks separated
+/// by blank lines and then single lines, until nothing more can go.
+///
+/// `input` itself should fail; if it doesn't it is returned unchanged.
+pub fn minimize(input: &str, mut fails: impl FnMut(&str) -> bool) -> String {
+    if !fails(input) {
+        return input.to_string();
+    }
+    let mut current = input.to_string();
+    loop {
+        let blocks: Vec<String> = current.split("\n\n").map(str::to_string).collect();
+        let blocks = ddmin(blocks, "\n\n", &mut fails);
+        let lines: Vec<String> = blocks.join("\n\n").lines().map(str::to_string).collect();
+        let smaller = ddmin(lines, "\n", &mut fails).join("\n");
+        if smaller == current {
+            return current;
+        }
+        current = smaller;
+    }
+}
+
+/// Zeller's delta debugging: removes ever smaller chunks of `units` as long as
+/// what is left, joined with `separator`, still fails.
+fn ddmin(
+    mut units: Vec<String>,
+    separator: &str,
+    fails: &mut impl FnMut(&str) -> bool,
+) -> Vec<String> {
+    let mut chunks = 2;
+    while units.len() > 1 {
+        let size = units.len().div_ceil(chunks);
+        let reduced = (0..units.len()).step_by(size).find_map(|start| {
+            let mut candidate = units.clone();
+            candidate.drain(start..(start + size).min(units.len()));
+            fails(&candidate.join(separator)).then_some(candidate)
+        });
+        match reduced {
+            Some(candidate) => {
+                units = candidate;
+                chunks = (chunks - 1).max(2);
+            }
+            None if chunks >= units.len() => break,
+            None => chunks = (chunks * 2).min(units.len()),
+        }
+    }
+    // A single unit left might not be needed either.
+    if units.len() == 1 && fails("") {
+        units.clear();
+    }
+    units
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{self, Entry};
+    use crate::{Answer, Solution};
+
+    /// A day that panics when any line says "boom".
+    struct Explosive;
+
+    impl Solution for Explosive {
+        const DAY: usize = 99;
+        const VERSION: u32 = 1;
+        type Parsed = Vec<String>;
+
+        fn parse(input: &str) -> Result<Self::Parsed> {
+            Ok(input.lines().map(str::to_string).collect())
+        }
+
+        fn part_1(lines: &Self::Parsed) -> Result<Answer> {
+            if lines.iter().any(|line| line == "boom") {
+                panic!("boom");
+            }
+            Ok(lines.len().into())
+        }
+    }
+
+    #[test]
+    fn test_parses_failures() {
+        assert_eq!("panic".parse::<Failure>().unwrap(), Failure::Panic);
+        assert_eq!("mismatch".parse::<Failure>().unwrap(), Failure::Mismatch);
+        assert!("slow".parse::<Failure>().is_err());
+    }
+
+    #[test]
+    fn test_keeps_only_the_failing_line() {
+        let input = (0..100)
+            .map(|i| {
+                if i == 63 {
+                    "bad".to_string()
+                } else {
+                    i.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let result = minimize(&input, |input| input.contains("bad"));
+
+        assert_eq!(result, "bad");
+    }
+
+    #[test]
+    fn test_keeps_lines_that_fail_together() {
+        let input = "a\nb\nc\nd\ne\nf\ng\nh";
+
+        let result = minimize(input, |input| input.contains('c') && input.contains('g'));
+
+        assert_eq!(result, "c\ng");
+    }
+
+    #[test]
+    fn test_drops_whole_blocks() {
+        let input = "1\n2\n\n3\n4\n\n5\n6";
+        let mut tries = Vec::new();
+
+        let result = minimize(input, |input| {
+            tries.push(input.to_string());
+            input.contains('4')
+        });
+
+        assert_eq!(result, "4");
+        assert!(tries.contains(&"3\n4".to_string()));
+    }
+
+    #[test]
+    fn test_returns_input_that_does_not_fail() {
+        assert_eq!(minimize("a\nb", |_| false), "a\nb");
+    }
+
+    #[test]
+    fn test_reproduces_panics_and_errors() {
+        let explosive = Entry::of::<Explosive>();
+
+        assert!(reproduces(Failure::Panic, 99, &[&explosive], "1\nboom"));
+        assert!(reproduces(Failure::Error, 99, &[&explosive], "1\nboom"));
+        assert!(!reproduces(Failure::Panic, 99, &[&explosive], "1\n2"));
+
+        let day_4 = runner::find(4).unwrap();
+        assert!(reproduces(Failure::Error, 4, &[day_4], "1-2,3"));
+        assert!(!reproduces(Failure::Panic, 4, &[day_4], "1-2,3"));
+    }
+
+    #[test]
+    fn test_minimizes_panicking_input() {
+        let explosive = Entry::of::<Explosive>();
+        let input = "a\nb\n\nc\nboom\nd\n\ne";
+
+        let result = minimize(input, |input| {
+            reproduces(Failure::Panic, 99, &[&explosive], input)
+        });
+
+        assert_eq!(result, "boom");
+    }
+
+    /// Counts lines like [`Explosive`], but miscounts when a line says "x".
+    struct Miscounting;
+
+    impl Solution for Miscounting {
+        const DAY: usize = 99;
+        const VERSION: u32 = 1;
+        const NAME: &'static str = "miscounting";
+        type Parsed = Vec<String>;
+
+        fn parse(input: &str) -> Result<Self::Parsed> {
+            Explosive::parse(input)
+        }
+
+        fn part_1(lines: &Self::Parsed) -> Result<Answer> {
+            let extra = lines.iter().filter(|line| *line == "x").count();
+            Ok((lines.len() + extra).into())
+        }
+    }
+
+    #[test]
+    fn test_minimizes_mismatching_input() {
+        let (explosive, miscounting) = (Entry::of::<Explosive>(), Entry::of::<Miscounting>());
+        let implementations: [&dyn Solver; 2] = [&explosive, &miscounting];
+        assert!(!reproduces(Failure::Mismatch, 99, &implementations, "a\nb"));
+
+        let result = minimize("a\nb\nx\nc", |input| {
+            reproduces(Failure::Mismatch, 99, &implementations, input)
+        });
+
+        assert_eq!(result, "x");
+    }
+}
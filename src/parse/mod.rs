@@ -0,0 +1,301 @@
+//! Helpers for inputs made of several sections, such as groups separated by
+//! blank lines or a drawing followed by a list of moves.
+//!
+//! Lines are kept together with their line number in the input, so a failure
+//! deep inside a section still says which line caused it.
+use anyhow::{Context, Error, Result};
+use std::str::FromStr;
+
+/// Consecutive lines of the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block<'a> {
+    /// The line number, counted from 1, of the first line.
+    pub first_line: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl<'a> Block<'a> {
+    /// All of `input` as a single block.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            first_line: 1,
+            lines: input.lines().collect(),
+        }
+    }
+
+    /// Each line with its line number.
+    pub fn numbered(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        (self.first_line..).zip(self.lines.iter().copied())
+    }
+
+    /// Parses every line with `parse`, saying which line failed.
+    pub fn parse_lines<T>(&self, mut parse: impl FnMut(&'a str) -> Result<T>) -> Result<Vec<T>> {
+        self.numbered()
+            .map(|(number, line)| parse(line).with_context(|| format!("Line {}", number)))
+            .collect()
+    }
+
+    /// Parses one integer per line. Anything else on a line, even another
+    /// integer, is an error.
+    pub fn integers<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Into<Error>,
+    {
+        self.parse_lines(|line| {
+            line.parse::<T>()
+                .map_err(Into::into)
+                .with_context(|| format!("Invalid number {:?}", line))
+        })
+    }
+
+    /// Parses every integer in the block, in order, whatever separates them
+    /// on a line, so `1 2 3` and `x=1, y=2` both work. Every line must have
+    /// at least one.
+    pub fn every_integer<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Into<Error>,
+    {
+        let lines = self.parse_lines(|line| {
+            let numbers = number_texts(line);
+            if numbers.is_empty() {
+                return Err(Error::msg(format!("Invalid number {:?}", line)));
+            }
+            numbers
+                .into_iter()
+                .map(|number| {
+                    number
+                        .parse::<T>()
+                        .map_err(Into::into)
+                        .with_context(|| format!("Invalid number {:?}", number))
+                })
+                .collect::<Result<Vec<T>>>()
+        })?;
+        Ok(lines.into_iter().flatten().collect())
+    }
+
+    /// Splits the block into the blocks between blank lines. Runs of blank
+    /// lines count as one separator, and empty blocks are left out.
+    pub fn blocks(&self) -> Vec<Block<'a>> {
+        let mut blocks = Vec::new();
+        let mut current: Option<Block<'a>> = None;
+        for (number, line) in self.numbered() {
+            if line.trim().is_empty() {
+                blocks.extend(current.take());
+            } else {
+                current
+                    .get_or_insert_with(|| Block {
+                        first_line: number,
+                        lines: Vec::new(),
+                    })
+                    .lines
+                    .push(line);
+            }
+        }
+        blocks.extend(current);
+        blocks
+    }
+
+    /// Splits the block at its first blank line into a header and a body,
+    /// dropping any further blank lines before the body. Without a blank
+    /// line the whole block is the header and the body is empty.
+    pub fn header_body(&self) -> (Block<'a>, Block<'a>) {
+        let split = self
+            .lines
+            .iter()
+            .position(|line| line.trim().is_empty())
+            .unwrap_or(self.lines.len());
+        let body_start = self.lines[split..]
+            .iter()
+            .position(|line| !line.trim().is_empty())
+            .map_or(self.lines.len(), |offset| split + offset);
+        (
+            Block {
+                first_line: self.first_line,
+                lines: self.lines[..split].to_vec(),
+            },
+            Block {
+                first_line: self.first_line + body_start,
+                lines: self.lines[body_start..].to_vec(),
+            },
+        )
+    }
+
+    /// Removes and returns the last line, failing if there are none.
+    pub fn pop(&mut self) -> Result<&'a str> {
+        self.lines.pop().ok_or_else(|| {
+            Error::msg(format!(
+                "Expected at least one line at line {}",
+                self.first_line
+            ))
+        })
+    }
+}
+
+/// Splits `input` into the blocks between blank lines.
+pub fn blocks(input: &str) -> Vec<Block<'_>> {
+    Block::new(input).blocks()
+}
+
+/// Splits `input` at its first blank line into a header and a body.
+pub fn header_body(input: &str) -> (Block<'_>, Block<'_>) {
+    Block::new(input).header_body()
+}
+
+/// Finds the integers written in `text`, such as `12` and `-3` in
+/// `x=12, y=-3`. A minus sign only counts at the start of a word, so `2-4` is
+/// a range rather than 2 and -4.
+pub fn number_texts(text: &str) -> Vec<&str> {
+    let mut numbers = Vec::new();
+    let mut previous = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let signed = c == '-'
+            && !previous.is_some_and(|previous: char| previous.is_alphanumeric())
+            && chars.peek().is_some_and(|(_, next)| next.is_ascii_digit());
+        if c.is_ascii_digit() || signed {
+            let mut end = start + c.len_utf8();
+            while let Some((index, next)) = chars.next_if(|(_, next)| next.is_ascii_digit()) {
+                end = index + next.len_utf8();
+            }
+            numbers.push(&text[start..end]);
+            previous = text[..end].chars().next_back();
+        } else {
+            previous = Some(c);
+        }
+    }
+    numbers
+}
+
+/// Cuts `line` into columns `width` characters wide with `gap` characters
+/// between them. The last column may be cut short when the line is.
+pub fn columns(line: &str, width: usize, gap: usize) -> Vec<&str> {
+    let boundaries: Vec<usize> = line
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([line.len()])
+        .collect();
+    let characters = boundaries.len() - 1;
+    (0..)
+        .map(|column| column * (width + gap))
+        .take_while(|&start| start < characters)
+        .map(|start| &line[boundaries[start]..boundaries[(start + width).min(characters)]])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splits_blocks_on_blank_lines() {
+        let result = blocks("1\n2\n\n3\n\n\n4\n");
+
+        assert_eq!(
+            result,
+            vec![
+                Block {
+                    first_line: 1,
+                    lines: vec!["1", "2"]
+                },
+                Block {
+                    first_line: 4,
+                    lines: vec!["3"]
+                },
+                Block {
+                    first_line: 7,
+                    lines: vec!["4"]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_has_no_blocks_without_lines() {
+        assert!(blocks("").is_empty());
+        assert!(blocks("\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_splits_header_from_body() {
+        let (header, body) = header_body("title\nmore\n\n\nbody\n");
+
+        assert_eq!(header.lines, vec!["title", "more"]);
+        assert_eq!(body.first_line, 5);
+        assert_eq!(body.lines, vec!["body"]);
+    }
+
+    #[test]
+    fn test_body_is_empty_without_blank_line() {
+        let (header, body) = header_body("title\nmore");
+
+        assert_eq!(header.lines, vec!["title", "more"]);
+        assert!(body.lines.is_empty());
+    }
+
+    #[test]
+    fn test_pops_last_line() {
+        let mut block = Block::new("a\nb");
+
+        assert_eq!(block.pop().unwrap(), "b");
+        assert_eq!(block.lines, vec!["a"]);
+        block.pop().unwrap();
+        assert!(block.pop().is_err());
+    }
+
+    #[test]
+    fn test_parses_integers() {
+        let result: Vec<u32> = Block::new("1\n22\n333").integers().unwrap();
+
+        assert_eq!(result, vec![1, 22, 333]);
+    }
+
+    #[test]
+    fn test_rejects_more_than_one_integer_per_line() {
+        for input in ["10 20", "12abc", "1.5", "1e3"] {
+            assert!(Block::new(input).integers::<u32>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parses_several_integers_on_a_line() {
+        let result: Vec<i32> = Block::new("1 2 3\nx=1, y=-2\n2-4,6-8")
+            .every_integer()
+            .unwrap();
+
+        assert_eq!(result, vec![1, 2, 3, 1, -2, 2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_reports_integer_that_does_not_fit() {
+        let error = Block::new("1 300").every_integer::<u8>().unwrap_err();
+
+        assert_eq!(error.to_string(), "Line 1");
+        assert!(format!("{:#}", error).contains("Invalid number \"300\""));
+    }
+
+    #[test]
+    fn test_reports_line_number_of_invalid_integer() {
+        let block = &blocks("1\n\n2\nx")[1];
+
+        let error = block.integers::<u32>().unwrap_err();
+
+        assert_eq!(error.to_string(), "Line 4");
+        assert!(format!("{:#}", error).contains("Invalid number \"x\""));
+    }
+
+    #[test]
+    fn test_cuts_fixed_width_columns() {
+        let result = columns("[A]     [C]", 3, 1);
+
+        assert_eq!(result, vec!["[A]", "   ", "[C]"]);
+    }
+
+    #[test]
+    fn test_cuts_short_last_column() {
+        let result = columns("ab cd e", 2, 1);
+
+        assert_eq!(result, vec!["ab", "cd", "e"]);
+    }
+}
@@ -0,0 +1,315 @@
+//! Line parsing from patterns like `"move {quantity} from {from} to {to}"`.
+//!
+//! [`line_pattern!`](crate::line_pattern) declares a struct together with the
+//! pattern its lines follow and implements `FromStr` for it. Each `{field}`
+//! takes the text up to the next literal part of the pattern and is parsed
+//! with the field type's own `FromStr`, so fields can be numbers, characters
+//! or other structs declared the same way.
+use anyhow::{Context, Error, Result};
+use std::str::FromStr;
+
+/// Declares a struct whose lines follow `pattern`, implementing `FromStr`:
+///
+/// ```
+/// aoc_2022::line_pattern! {
+///     "{min}-{max}"
+///     #[derive(Debug)]
+///     pub struct Range {
+///         pub min: usize,
+///         pub max: usize,
+///     }
+/// }
+///
+/// let range: Range = "2-4".parse().unwrap();
+/// assert_eq!((range.min, range.max), (2, 4));
+/// ```
+///
+/// The pattern must name every field exactly once, and two fields need some
+/// literal text between them.
+#[macro_export]
+macro_rules! line_pattern {
+    (
+        $pattern:literal
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident : $type:ty),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $type),*
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = ::anyhow::Error;
+
+            fn from_str(line: &str) -> ::anyhow::Result<Self> {
+                static PATTERN: ::std::sync::OnceLock<$crate::pattern::Pattern> =
+                    ::std::sync::OnceLock::new();
+                let captures = PATTERN
+                    .get_or_init(|| {
+                        $crate::pattern::Pattern::new($pattern, &[$(stringify!($field)),*])
+                    })
+                    .captures(line)?;
+                Ok(Self {
+                    $($field: captures.parse(stringify!($field))?),*
+                })
+            }
+        }
+    };
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(&'static str),
+    Field(&'static str),
+}
+
+/// A parsed pattern, split into literal text and fields.
+#[derive(Debug)]
+pub struct Pattern {
+    segments: Vec<Segment>,
+}
+
+/// The text each field of a pattern matched in a line.
+pub struct Captures<'a> {
+    line: &'a str,
+    /// Each field with the byte offset and text it matched.
+    fields: Vec<(&'static str, usize, &'a str)>,
+}
+
+impl Pattern {
+    /// Splits `template` into literals and `{field}`s.
+    ///
+    /// Panics if the template is malformed or doesn't name exactly `fields`,
+    /// since that is a mistake in the code rather than in the input.
+    pub fn new(template: &'static str, fields: &[&str]) -> Self {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            match rest.find('{') {
+                Some(0) => {
+                    let end = rest
+                        .find('}')
+                        .unwrap_or_else(|| panic!("Unclosed field in pattern {:?}", template));
+                    if let Some(Segment::Field(_)) = segments.last() {
+                        panic!("Fields need text between them in pattern {:?}", template);
+                    }
+                    segments.push(Segment::Field(&rest[1..end]));
+                    rest = &rest[end + 1..];
+                }
+                Some(start) => {
+                    segments.push(Segment::Literal(&rest[..start]));
+                    rest = &rest[start..];
+                }
+                None => {
+                    segments.push(Segment::Literal(rest));
+                    rest = "";
+                }
+            }
+        }
+
+        let mut named: Vec<&str> = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Field(name) => Some(*name),
+                Segment::Literal(_) => None,
+            })
+            .collect();
+        let mut expected = fields.to_vec();
+        named.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(
+            named, expected,
+            "Pattern {:?} must name each field once",
+            template
+        );
+        Self { segments }
+    }
+
+    /// Matches `line` against the pattern. Each field takes the text up to
+    /// the next literal, or the rest of the line if it comes last.
+    pub fn captures<'a>(&self, line: &'a str) -> Result<Captures<'a>> {
+        let mut fields = Vec::new();
+        let mut position = 0;
+        for (index, segment) in self.segments.iter().enumerate() {
+            let rest = &line[position..];
+            match segment {
+                Segment::Literal(literal) => {
+                    if !rest.starts_with(literal) {
+                        return Err(Error::msg(format!(
+                            "Expected {:?} at column {} of {:?}",
+                            literal,
+                            column(line, position),
+                            line
+                        )));
+                    }
+                    position += literal.len();
+                }
+                Segment::Field(name) => {
+                    let length = match self.segments.get(index + 1) {
+                        Some(Segment::Literal(literal)) => rest.find(literal).ok_or_else(|| {
+                            Error::msg(format!(
+                                "Expected {:?} after column {} of {:?}",
+                                literal,
+                                column(line, position),
+                                line
+                            ))
+                        })?,
+                        _ => rest.len(),
+                    };
+                    fields.push((*name, position, &rest[..length]));
+                    position += length;
+                }
+            }
+        }
+        if position < line.len() {
+            return Err(Error::msg(format!(
+                "Unexpected {:?} at column {} of {:?}",
+                &line[position..],
+                column(line, position),
+                line
+            )));
+        }
+        Ok(Captures { line, fields })
+    }
+}
+
+impl Captures<'_> {
+    /// Parses the text matched by the field called `name`.
+    pub fn parse<T>(&self, name: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Into<Error>,
+    {
+        let (_, position, text) = self
+            .fields
+            .iter()
+            .find(|(field, _, _)| *field == name)
+            .ok_or_else(|| Error::msg(format!("No field called {}", name)))?;
+        text.parse::<T>().map_err(Into::into).with_context(|| {
+            format!(
+                "Invalid {} {:?} at column {} of {:?}",
+                name,
+                text,
+                column(self.line, *position),
+                self.line
+            )
+        })
+    }
+}
+
+/// The column, counted from 1, that `position` is at in `line`.
+fn column(line: &str, position: usize) -> usize {
+    line[..position].chars().count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::line_pattern! {
+        "move {quantity} from {from} to {to}"
+        #[derive(Debug, PartialEq)]
+        struct Move {
+            quantity: usize,
+            from: usize,
+            to: usize,
+        }
+    }
+
+    crate::line_pattern! {
+        "{name}: {start}..{end}"
+        #[derive(Debug, PartialEq)]
+        struct Labelled {
+            name: String,
+            start: Point,
+            end: Point,
+        }
+    }
+
+    crate::line_pattern! {
+        "({x},{y})"
+        #[derive(Debug, PartialEq)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+    }
+
+    #[test]
+    fn test_parses_typed_fields() {
+        let result: Move = "move 3 from 1 to 12".parse().unwrap();
+
+        assert_eq!(
+            result,
+            Move {
+                quantity: 3,
+                from: 1,
+                to: 12
+            }
+        );
+    }
+
+    #[test]
+    fn test_parses_nested_patterns() {
+        let result: Labelled = "edge: (1,-2)..(30,4)".parse().unwrap();
+
+        assert_eq!(result.name, "edge");
+        assert_eq!(result.start, Point { x: 1, y: -2 });
+        assert_eq!(result.end, Point { x: 30, y: 4 });
+    }
+
+    #[test]
+    fn test_reports_column_of_missing_literal() {
+        let error = "move 3 to 1".parse::<Move>().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            r#"Expected " from " after column 6 of "move 3 to 1""#
+        );
+    }
+
+    #[test]
+    fn test_reports_column_of_wrong_prefix() {
+        let error = "mov 3 from 1 to 2".parse::<Move>().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            r#"Expected "move " at column 1 of "mov 3 from 1 to 2""#
+        );
+    }
+
+    #[test]
+    fn test_reports_column_of_invalid_field() {
+        let error = "move 3 from x to 2".parse::<Move>().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            r#"Invalid from "x" at column 13 of "move 3 from x to 2""#
+        );
+        assert!(format!("{:#}", error).contains("invalid digit"));
+    }
+
+    #[test]
+    fn test_rejects_trailing_text() {
+        let error = "(1,2)!".parse::<Point>().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            r#"Unexpected "!" at column 6 of "(1,2)!""#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must name each field once")]
+    fn test_pattern_must_name_every_field() {
+        Pattern::new("{a}-{b}", &["a", "c"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fields need text between them")]
+    fn test_fields_need_text_between_them() {
+        Pattern::new("{a}{b}", &["a", "b"]);
+    }
+}
@@ -0,0 +1,239 @@
+use super::files::FileReader;
+use super::runner::{self, Entry};
+use super::NotImplemented;
+use anyhow::{Context, Error, Result};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+const HELP: &str = "\
+Commands
+  :day <n>       Pick a day, loading assets/day_N/input.txt
+  :input <path>  Load the input from another file
+  :paste         Paste example text, ending with a line holding only `.`
+  :run [part]    Solve both parts, or just one, with timings
+  :parsed        Show the parsed input
+  :show          Show the current input
+  :help          Show this help
+  :quit          Leave";
+
+/// Where the current input came from.
+enum Source {
+    File(PathBuf),
+    Pasted,
+}
+
+/// An interactive session for trying days against different inputs.
+pub struct Repl {
+    file_reader: Box<dyn FileReader>,
+    entry: Option<&'static Entry>,
+    input: Option<(Source, String)>,
+}
+
+impl Repl {
+    pub fn new(file_reader: Box<dyn FileReader>) -> Self {
+        Self {
+            file_reader,
+            entry: None,
+            input: None,
+        }
+    }
+
+    /// Reads commands from `input` until it ends or `:quit`, writing replies
+    /// to `output`. Failed commands are reported and the session carries on.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        let mut lines = input.lines();
+        loop {
+            write!(output, "{}", self.prompt())?;
+            output.flush()?;
+            let Some(line) = lines.next() else {
+                writeln!(output)?;
+                return Ok(());
+            };
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == ":quit" || line == ":q" {
+                return Ok(());
+            }
+            let reply = if line == ":paste" {
+                let mut pasted = Vec::new();
+                for line in lines.by_ref() {
+                    let line = line?;
+                    if line == "." {
+                        break;
+                    }
+                    pasted.push(line);
+                }
+                self.paste(pasted.join("\n"))
+            } else {
+                self.eval(line)
+            };
+            match reply {
+                Ok(reply) => writeln!(output, "{}", reply.trim_end())?,
+                Err(error) => writeln!(output, "Error: {:#}", error)?,
+            }
+        }
+    }
+
+    fn prompt(&self) -> String {
+        match self.entry {
+            Some(entry) => format!("day {}> ", entry.day),
+            None => "> ".to_string(),
+        }
+    }
+
+    fn eval(&mut self, line: &str) -> Result<String> {
+        let (command, argument) = match line.split_once(' ') {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+        match command {
+            ":day" => self.pick_day(argument),
+            ":input" => self.load(Path::new(argument)),
+            ":run" => self.solve(argument),
+            ":parsed" => (self.entry()?.describe)(self.input()?),
+            ":show" => Ok(self.input()?.to_string()),
+            ":help" => Ok(HELP.to_string()),
+            _ => Err(Error::msg(format!(
+                "Unknown command {}, try :help",
+                command
+            ))),
+        }
+    }
+
+    fn pick_day(&mut self, argument: &str) -> Result<String> {
+        let day = argument
+            .parse()
+            .with_context(|| format!("Invalid day: {}", argument))?;
+        let entry = runner::find(day).ok_or(NotImplemented { day, part: None })?;
+        self.entry = Some(entry);
+        match self.load(&entry.input_path()) {
+            Ok(reply) => Ok(format!("Day {}, {}", day, reply)),
+            Err(error) => {
+                self.input = None;
+                Ok(format!("Day {}, no input loaded: {:#}", day, error))
+            }
+        }
+    }
+
+    fn load(&mut self, path: &Path) -> Result<String> {
+        if path.as_os_str().is_empty() {
+            return Err(Error::msg("Missing path, use :input <path>"));
+        }
+        let input = self
+            .file_reader
+            .read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let reply = format!(
+            "loaded {} ({} lines)",
+            path.display(),
+            input.lines().count()
+        );
+        self.input = Some((Source::File(path.to_path_buf()), input));
+        Ok(reply)
+    }
+
+    fn paste(&mut self, input: String) -> Result<String> {
+        let reply = format!("Pasted {} lines", input.lines().count());
+        self.input = Some((Source::Pasted, input));
+        Ok(reply)
+    }
+
+    fn solve(&self, argument: &str) -> Result<String> {
+        let entry = self.entry()?;
+        let mut report = runner::run_input(entry, self.input()?);
+        if !argument.is_empty() {
+            let part: usize = argument
+                .parse()
+                .with_context(|| format!("Invalid part: {}", argument))?;
+            report.parts.retain(|report| report.part == part);
+        }
+        let source = match &self.input {
+            Some((Source::File(path), _)) => path.display().to_string(),
+            _ => "pasted input".to_string(),
+        };
+        Ok(format!("{}  Input: {}", report, source))
+    }
+
+    fn entry(&self) -> Result<&'static Entry> {
+        self.entry
+            .ok_or_else(|| Error::msg("No day picked, use :day <n>"))
+    }
+
+    fn input(&self) -> Result<&str> {
+        match &self.input {
+            Some((_, input)) => Ok(input),
+            None => Err(Error::msg("No input loaded, use :input or :paste")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::InMemoryReader;
+    use std::collections::HashMap;
+
+    fn session(commands: &str) -> String {
+        let files = HashMap::from([
+            (
+                PathBuf::from("assets/day_4/input.txt"),
+                "2-4,6-8\n2-8,3-7".to_string(),
+            ),
+            (PathBuf::from("other.txt"), "6-6,4-6".to_string()),
+        ]);
+        let mut repl = Repl::new(Box::new(InMemoryReader::from(files)));
+        let mut output = Vec::new();
+        repl.run(commands.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_runs_picked_day() {
+        let output = session(":day 4\n:run\n");
+
+        assert!(output.contains("Day 4, loaded assets/day_4/input.txt (2 lines)"));
+        assert!(output.contains("day 4> "));
+        assert!(output.contains("Part 1: 1 ("));
+        assert!(output.contains("Part 2: 1 ("));
+    }
+
+    #[test]
+    fn test_runs_one_part_of_another_input() {
+        let output = session(":day 4\n:input other.txt\n:run 2\n");
+
+        assert!(output.contains("Part 2: 1 ("));
+        assert!(!output.contains("Part 1:"));
+        assert!(output.contains("Input: other.txt"));
+    }
+
+    #[test]
+    fn test_runs_pasted_input() {
+        let output = session(":day 4\n:paste\n2-4,6-8\n2-3,4-5\n5-7,7-9\n.\n:run\n");
+
+        assert!(output.contains("Pasted 3 lines"));
+        assert!(output.contains("Part 1: 0 ("));
+        assert!(output.contains("Part 2: 1 ("));
+        assert!(output.contains("Input: pasted input"));
+    }
+
+    #[test]
+    fn test_shows_parsed_input() {
+        let output = session(":day 5\n:paste\n[A]\n 1\n\nmove 1 from 1 to 1\n.\n:parsed\n");
+
+        assert!(output.contains("stacks: ["));
+        assert!(output.contains("quantity: 1,"));
+    }
+
+    #[test]
+    fn test_reports_errors_and_carries_on() {
+        let output = session(":run\n:day 99\n:nonsense\n:day 4\n:quit\n:run\n");
+
+        assert!(output.contains("Error: No day picked"));
+        assert!(output.contains("Error: Day 99 is not implemented yet"));
+        assert!(output.contains("Error: Unknown command :nonsense"));
+        assert!(!output.contains("Part 1:"));
+    }
+}
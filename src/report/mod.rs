@@ -0,0 +1,278 @@
+use super::bench::Statistics;
+use super::calendar::{self, Progress};
+use super::files::FileReader;
+use super::runner;
+use anyhow::{Error, Result};
+use std::fmt;
+use std::time::Duration;
+
+/// Marks the start of the section of a Markdown file the report replaces.
+pub const START: &str = "<!-- report:start -->";
+/// Marks the end of the section the report replaces.
+pub const END: &str = "<!-- report:end -->";
+
+const HEADINGS: [&str; 6] = ["Day", "Puzzle", "Parts", "Verified", "Parse", "Solve"];
+
+/// Progress and timings of one registered day.
+#[derive(Debug, PartialEq)]
+pub struct Row {
+    pub day: usize,
+    pub title: &'static str,
+    pub parts: [Progress; 2],
+    /// Mean parse time in the last benchmark.
+    pub parse: Option<Duration>,
+    /// Mean time to solve every benchmarked part in the last benchmark.
+    pub solve: Option<Duration>,
+}
+
+pub struct Report {
+    pub rows: Vec<Row>,
+}
+
+/// Checks every registered day against its stored answers and takes its
+/// timings from `benchmark`.
+pub fn report(file_reader: &dyn FileReader, benchmark: &[Statistics]) -> Report {
+    let rows = runner::DAYS
+        .iter()
+        .map(|entry| {
+            let day = entry.day;
+            let phases: Vec<&Statistics> = benchmark
+                .iter()
+                .filter(|statistics| statistics.day == day)
+                .collect();
+            let mean = |statistics: &Statistics| Duration::from_nanos(statistics.mean as u64);
+            let parts: Vec<Duration> = phases
+                .iter()
+                .filter(|statistics| statistics.phase.starts_with("part "))
+                .map(|statistics| mean(statistics))
+                .collect();
+            Row {
+                day,
+                title: entry.title,
+                parts: calendar::status(file_reader, day, 0).parts,
+                parse: phases
+                    .iter()
+                    .find(|statistics| statistics.phase == "parse")
+                    .map(|statistics| mean(statistics)),
+                solve: (!parts.is_empty()).then(|| parts.iter().sum()),
+            }
+        })
+        .collect();
+    Report { rows }
+}
+
+impl Row {
+    /// The parts that are implemented, such as `1, 2`.
+    fn implemented(&self) -> String {
+        let parts: Vec<String> = (1..=2)
+            .filter(|part| self.parts[part - 1] != Progress::NotImplemented)
+            .map(|part| part.to_string())
+            .collect();
+        if parts.is_empty() {
+            "-".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+
+    /// Whether the implemented parts give the stored answers.
+    fn verified(&self) -> String {
+        let with = |progress: Progress| (1..=2).find(|part| self.parts[part - 1] == progress);
+        if let Some(part) = with(Progress::Wrong) {
+            format!("part {} wrong", part)
+        } else if let Some(part) = with(Progress::Unverified) {
+            format!("part {} unchecked", part)
+        } else if with(Progress::Verified).is_some() {
+            "yes".to_string()
+        } else {
+            "-".to_string()
+        }
+    }
+
+    fn cells(&self, title: String) -> [String; 6] {
+        let time = |time: Option<Duration>| time.map_or("-".to_string(), |t| format!("{:.1?}", t));
+        [
+            self.day.to_string(),
+            title,
+            self.implemented(),
+            self.verified(),
+            time(self.parse),
+            time(self.solve),
+        ]
+    }
+}
+
+impl Report {
+    /// The report as a Markdown table, linking each puzzle.
+    pub fn markdown(&self) -> String {
+        let mut table = format!("| {} |\n", HEADINGS.join(" | "));
+        table.push_str("|----:|--------|-------|----------|------:|------:|\n");
+        for row in &self.rows {
+            let title = format!(
+                "[{}](https://adventofcode.com/2022/day/{})",
+                row.title, row.day
+            );
+            table.push_str(&format!("| {} |\n", row.cells(title).join(" | ")));
+        }
+        table.push_str("\nTimes are means from the last `cargo run --release -- bench`.\n");
+        table
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows: Vec<[String; 6]> = self
+            .rows
+            .iter()
+            .map(|row| row.cells(row.title.to_string()))
+            .collect();
+        let widths: Vec<usize> = (0..HEADINGS.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .chain([HEADINGS[column].len()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let headings = HEADINGS.map(str::to_string);
+        for row in [&headings].into_iter().chain(&rows) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Replaces whatever is between the [`START`] and [`END`] markers in
+/// `document` with `section`, keeping the markers.
+pub fn replace_section(document: &str, section: &str) -> Result<String> {
+    let start = document
+        .find(START)
+        .ok_or_else(|| Error::msg(format!("No {} marker", START)))?
+        + START.len();
+    let end = document[start..]
+        .find(END)
+        .ok_or_else(|| Error::msg(format!("No {} marker after {}", END, START)))?
+        + start;
+    Ok(format!(
+        "{}\n{}{}",
+        &document[..start],
+        section,
+        &document[end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::InMemoryReader;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn row(day: usize, parts: [Progress; 2]) -> Row {
+        Row {
+            day,
+            title: "Camp Cleanup",
+            parts,
+            parse: Some(Duration::from_micros(12)),
+            solve: None,
+        }
+    }
+
+    #[test]
+    fn test_takes_times_from_benchmark() {
+        let reader = InMemoryReader::from(HashMap::<PathBuf, String>::new());
+        let benchmark = [
+            Statistics::from_durations(4, "parse", &[Duration::from_micros(10)]),
+            Statistics::from_durations(4, "part 1", &[Duration::from_micros(2)]),
+            Statistics::from_durations(4, "part 2", &[Duration::from_micros(3)]),
+        ];
+
+        let result = report(&reader, &benchmark);
+
+        let day_4 = &result.rows[3];
+        assert_eq!((day_4.day, day_4.title), (4, "Camp Cleanup"));
+        assert_eq!(day_4.parse, Some(Duration::from_micros(10)));
+        assert_eq!(day_4.solve, Some(Duration::from_micros(5)));
+        assert_eq!((result.rows[0].parse, result.rows[0].solve), (None, None));
+    }
+
+    #[test]
+    fn test_describes_verification() {
+        let verified = row(4, [Progress::Verified, Progress::NotImplemented]);
+        let unchecked = row(4, [Progress::Verified, Progress::Unverified]);
+        let wrong = row(4, [Progress::Unverified, Progress::Wrong]);
+        let missing = row(4, [Progress::NotImplemented; 2]);
+
+        assert_eq!(
+            (verified.implemented(), verified.verified()),
+            ("1".into(), "yes".into())
+        );
+        assert_eq!(unchecked.verified(), "part 2 unchecked");
+        assert_eq!(wrong.verified(), "part 2 wrong");
+        assert_eq!(
+            (missing.implemented(), missing.verified()),
+            ("-".into(), "-".into())
+        );
+    }
+
+    #[test]
+    fn test_renders_markdown_table() {
+        let report = Report {
+            rows: vec![row(4, [Progress::Verified; 2])],
+        };
+
+        let result = report.markdown();
+
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(
+            lines[0],
+            "| Day | Puzzle | Parts | Verified | Parse | Solve |"
+        );
+        assert_eq!(
+            lines[2],
+            "| 4 | [Camp Cleanup](https://adventofcode.com/2022/day/4) | 1, 2 | yes | 12.0µs | - |"
+        );
+    }
+
+    #[test]
+    fn test_aligns_plain_columns() {
+        let report = Report {
+            rows: vec![row(4, [Progress::Verified; 2])],
+        };
+
+        let result = report.to_string();
+
+        assert_eq!(
+            result,
+            "Day  Puzzle        Parts  Verified  Parse   Solve\n\
+             4    Camp Cleanup  1, 2   yes       12.0µs  -\n"
+        );
+    }
+
+    #[test]
+    fn test_replaces_marked_section() {
+        let document = format!("# Title\n{}\nold\n{}\nafter\n", START, END);
+
+        let result = replace_section(&document, "new\n").unwrap();
+
+        assert_eq!(result, format!("# Title\n{}\nnew\n{}\nafter\n", START, END));
+        assert_eq!(replace_section(&result, "new\n").unwrap(), result);
+    }
+
+    #[test]
+    fn test_requires_both_markers() {
+        let error = replace_section("# Title\n", "new\n").unwrap_err();
+        assert_eq!(error.to_string(), format!("No {} marker", START));
+
+        let error = replace_section(&format!("{}\n{}\n", END, START), "new\n").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with(&format!("No {} marker after", END)));
+    }
+}
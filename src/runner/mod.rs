@@ -0,0 +1,880 @@
+use super::crash::{self, Crash};
+use super::files::FileReader;
+use super::generate::Rng;
+use super::memory::{self, MemoryUsage};
+use super::{day_1, day_2, day_3, day_4, day_5, day_6};
+use super::{Answer, NotImplemented, Solution};
+use anyhow::{Context, Error, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A registered day, with its solution erased so days can be run by number.
+pub struct Entry {
+    pub day: usize,
+    pub name: &'static str,
+    pub title: &'static str,
+    pub version: u32,
+    pub solve: fn(usize, &str) -> Result<Answer>,
+    /// Parses the input and pretty-prints the parsed model.
+    pub describe: fn(&str) -> Result<String>,
+    /// Parses the input and serializes the parsed model.
+    pub dump: fn(&str) -> Result<serde_json::Value>,
+    pub generate: fn(&mut Rng, usize) -> Option<String>,
+    run: fn(&str, &mut dyn FnMut(Event)) -> Result<()>,
+}
+
+impl Entry {
+    pub const fn of<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            name: S::NAME,
+            title: S::TITLE,
+            version: S::VERSION,
+            solve: S::solve,
+            describe: describe::<S>,
+            dump: dump::<S>,
+            generate: S::generate,
+            run: run_phases::<S>,
+        }
+    }
+
+    pub fn input_path(&self) -> PathBuf {
+        input_path(self.day)
+    }
+}
+
+/// Where the puzzle input of `day` is kept.
+pub fn input_path(day: usize) -> PathBuf {
+    PathBuf::from(format!("assets/day_{}/input.txt", day))
+}
+
+pub const DAYS: &[Entry] = &[
+    Entry::of::<day_1::Day1>(),
+    Entry::of::<day_2::Day2>(),
+    Entry::of::<day_3::Day3>(),
+    Entry::of::<day_4::Day4>(),
+    Entry::of::<day_5::Day5>(),
+    Entry::of::<day_6::Day6>(),
+];
+
+/// Other implementations of registered days, picked by name.
+pub const ALTERNATIVES: &[Entry] = &[Entry::of::<day_6::Day6Window>()];
+
+pub fn find(day: usize) -> Option<&'static Entry> {
+    DAYS.iter().find(|entry| entry.day == day)
+}
+
+/// Every implementation of the day, the registered one first.
+pub fn implementations(day: usize) -> Vec<&'static Entry> {
+    DAYS.iter()
+        .chain(ALTERNATIVES)
+        .filter(|entry| entry.day == day)
+        .collect()
+}
+
+/// Finds the implementation of `day` called `name` among `implementations`,
+/// listing the names there are when none matches. Lets callers that know of
+/// more implementations than the registered ones, such as external solvers,
+/// search them the same way.
+pub fn find_named<'a, T>(
+    day: usize,
+    name: &str,
+    implementations: Vec<T>,
+    name_of: impl Fn(&T) -> &'a str,
+) -> Result<T> {
+    if implementations.is_empty() {
+        return Err(NotImplemented { day, part: None }.into());
+    }
+    let names: Vec<&str> = implementations.iter().map(&name_of).collect();
+    implementations
+        .into_iter()
+        .find(|implementation| name_of(implementation) == name)
+        .ok_or_else(|| {
+            Error::msg(format!(
+                "Day {} has no implementation called {}, try one of: {}",
+                day,
+                name,
+                names.join(", ")
+            ))
+        })
+}
+
+/// Anything that can solve a day's input, built in or not.
+pub trait Solver: Sync {
+    fn name(&self) -> &str;
+    /// Solves both parts of `input` for `day`.
+    fn run_input(&self, day: usize, input: &str) -> DayReport;
+}
+
+impl Solver for Entry {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn run_input(&self, _day: usize, input: &str) -> DayReport {
+        run_input(self, input)
+    }
+}
+
+/// The answer to one part and how long it took to solve.
+pub struct PartReport {
+    pub part: usize,
+    pub answer: Result<Answer>,
+    pub elapsed: Duration,
+    /// Heap usage while solving, when memory is being counted.
+    pub memory: Option<MemoryUsage>,
+    /// True when the answer came from the cache instead of being solved.
+    pub cached: bool,
+}
+
+/// How running a day ended.
+pub enum Status {
+    Completed,
+    Failed(Error),
+    TimedOut(Duration),
+}
+
+/// Everything that happened when running a day, phase by phase. Phases that
+/// never finished are left empty.
+pub struct DayReport {
+    pub day: usize,
+    pub read: Option<Duration>,
+    pub parse: Option<Duration>,
+    pub read_memory: Option<MemoryUsage>,
+    pub parse_memory: Option<MemoryUsage>,
+    pub parts: Vec<PartReport>,
+    pub status: Status,
+    /// Wall clock time from starting the day until it finished or was
+    /// abandoned.
+    pub wall: Duration,
+    /// What the day printed, when it ran in a child process.
+    pub output: Option<String>,
+}
+
+/// Progress sent from a running day as each phase finishes.
+enum Event {
+    Read(Duration, Option<MemoryUsage>),
+    Parsed(Duration, Option<MemoryUsage>),
+    Solved(PartReport),
+    Finished(Result<()>),
+}
+
+fn describe<S: Solution>(input: &str) -> Result<String> {
+    Ok(format!("{:#?}", S::parse(input)?))
+}
+
+fn dump<S: Solution>(input: &str) -> Result<serde_json::Value> {
+    Ok(serde_json::to_value(S::parse(input)?)?)
+}
+
+fn run_phases<S: Solution>(input: &str, emit: &mut dyn FnMut(Event)) -> Result<()> {
+    let start = Instant::now();
+    let (parsed, memory) = memory::measure(|| crash::catch(S::DAY, None, || S::parse(input)));
+    let parsed = parsed?;
+    emit(Event::Parsed(start.elapsed(), memory));
+
+    for part in 1..=2 {
+        let start = Instant::now();
+        let (answer, memory) =
+            memory::measure(|| crash::catch(S::DAY, Some(part), || S::part(part, &parsed)));
+        emit(Event::Solved(PartReport {
+            part,
+            answer,
+            elapsed: start.elapsed(),
+            memory,
+            cached: false,
+        }));
+    }
+    Ok(())
+}
+
+fn execute(
+    entry: &Entry,
+    file_reader: &dyn FileReader,
+    path: &Path,
+    emit: &mut dyn FnMut(Event),
+) -> Result<()> {
+    let start = Instant::now();
+    let (input, memory) = memory::measure(|| file_reader.read_to_string(path));
+    let input = input.with_context(|| format!("Failed to read {}", path.display()))?;
+    emit(Event::Read(start.elapsed(), memory));
+
+    (entry.run)(&input, emit).with_context(|| format!("Failed to parse day {}", entry.day))
+}
+
+/// Reads the day's input from `path`, parses it once and solves both parts,
+/// timing each phase separately.
+pub fn run_day(entry: &Entry, file_reader: &dyn FileReader, path: &Path) -> DayReport {
+    let start = Instant::now();
+    let mut report = DayReport::new(entry.day);
+    let result = execute(entry, file_reader, path, &mut |event| report.apply(event));
+    report.apply(Event::Finished(result));
+    report.wall = start.elapsed();
+    report
+}
+
+/// Parses `input` and solves both parts, without reading anything.
+pub fn run_input(entry: &Entry, input: &str) -> DayReport {
+    let start = Instant::now();
+    let mut report = DayReport::new(entry.day);
+    let result = (entry.run)(input, &mut |event| report.apply(event))
+        .with_context(|| format!("Failed to parse day {}", entry.day));
+    report.apply(Event::Finished(result));
+    report.wall = start.elapsed();
+    report
+}
+
+/// Reads the input from `path` and solves it with `solver`, for solvers that
+/// aren't registered days, such as external programs.
+pub fn run_solver(
+    solver: &dyn Solver,
+    day: usize,
+    file_reader: &dyn FileReader,
+    path: &Path,
+) -> DayReport {
+    let start = Instant::now();
+    let mut report = match file_reader.read_to_string(path) {
+        Ok(input) => {
+            let read = start.elapsed();
+            let mut report = solver.run_input(day, &input);
+            report.read = Some(read);
+            report
+        }
+        Err(error) => {
+            let mut report = DayReport::new(day);
+            report.status =
+                Status::Failed(error.context(format!("Failed to read {}", path.display())));
+            report
+        }
+    };
+    report.wall = start.elapsed();
+    report
+}
+
+/// Runs the day on a worker thread, giving up on it once `limit` has passed.
+///
+/// Threads can't be stopped from outside, so a day that times out is left
+/// running in the background until the process exits.
+pub fn run_day_with_limit(
+    entry: &'static Entry,
+    file_reader: Arc<dyn FileReader>,
+    path: PathBuf,
+    limit: Duration,
+) -> DayReport {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let result = execute(entry, file_reader.as_ref(), &path, &mut |event| {
+            let _ = sender.send(event);
+        });
+        let _ = sender.send(Event::Finished(result));
+    });
+
+    let start = Instant::now();
+    let mut report = DayReport::new(entry.day);
+    let deadline = start + limit;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(remaining) {
+            Ok(Event::Finished(result)) => {
+                report.apply(Event::Finished(result));
+                break;
+            }
+            Ok(event) => report.apply(event),
+            Err(RecvTimeoutError::Timeout) => {
+                report.status = Status::TimedOut(limit);
+                break;
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                report.status = Status::Failed(Error::msg("Worker thread stopped unexpectedly"));
+                break;
+            }
+        }
+    }
+    report.wall = start.elapsed();
+    report
+}
+
+/// Runs the day in a child process started from `command`, which should run
+/// only this day and print its own report.
+///
+/// This also survives crashes that can't be caught in process, such as
+/// aborts and stack overflows, and a child that runs past `limit` is killed.
+pub fn run_day_in_process(day: usize, mut command: Command, limit: Option<Duration>) -> DayReport {
+    let start = Instant::now();
+    let mut report = DayReport::new(day);
+    match command.stdout(Stdio::piped()).spawn() {
+        Ok(mut child) => {
+            // Drain the output as it comes so a chatty child can't block on a
+            // full pipe while we wait for it.
+            let output = child.stdout.take().map(|mut stdout| {
+                thread::spawn(move || {
+                    let mut output = String::new();
+                    let _ = stdout.read_to_string(&mut output);
+                    output
+                })
+            });
+            report.status = wait_for_child(child, limit);
+            // Anything the killed child started may still hold the pipe open,
+            // so only wait for the output of children that exited.
+            if !matches!(report.status, Status::TimedOut(_)) {
+                report.output = output.and_then(|output| output.join().ok());
+            }
+        }
+        Err(error) => {
+            report.status =
+                Status::Failed(Error::new(error).context("Failed to start child process"))
+        }
+    };
+    report.wall = start.elapsed();
+    report
+}
+
+/// Calls `run` for every entry on up to `jobs` threads at once, passing each
+/// result to `on_result` in the same order as `entries` as soon as it and
+/// every result before it are ready.
+pub fn run_parallel<E: Copy + Sync, T: Send>(
+    entries: &[E],
+    jobs: usize,
+    run: impl Fn(E) -> T + Sync,
+    mut on_result: impl FnMut(T),
+) {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, entries.len().max(1)) {
+            let sender = sender.clone();
+            let (next, run) = (&next, &run);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(entry) = entries.get(index) else {
+                    break;
+                };
+                if sender.send((index, run(*entry))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&expected) {
+                on_result(result);
+                expected += 1;
+            }
+        }
+    });
+}
+
+fn wait_for_child(mut child: Child, limit: Option<Duration>) -> Status {
+    let exit_status = match limit {
+        Some(limit) => {
+            let deadline = Instant::now() + limit;
+            loop {
+                match child.try_wait() {
+                    Ok(None) if Instant::now() >= deadline => {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        return Status::TimedOut(limit);
+                    }
+                    Ok(None) => thread::sleep(Duration::from_millis(1)),
+                    Ok(Some(exit_status)) => break Ok(exit_status),
+                    Err(error) => break Err(error),
+                }
+            }
+        }
+        None => child.wait(),
+    };
+
+    match exit_status {
+        Ok(exit_status) if exit_status.success() => Status::Completed,
+        Ok(exit_status) => Status::Failed(Error::msg(format!(
+            "Child process exited with {}",
+            exit_status
+        ))),
+        Err(error) => Status::Failed(error.into()),
+    }
+}
+
+impl DayReport {
+    pub(crate) fn new(day: usize) -> Self {
+        Self {
+            day,
+            read: None,
+            parse: None,
+            read_memory: None,
+            parse_memory: None,
+            parts: Vec::new(),
+            status: Status::Completed,
+            wall: Duration::ZERO,
+            output: None,
+        }
+    }
+
+    fn apply(&mut self, event: Event) {
+        match event {
+            Event::Read(elapsed, memory) => {
+                self.read = Some(elapsed);
+                self.read_memory = memory;
+            }
+            Event::Parsed(elapsed, memory) => {
+                self.parse = Some(elapsed);
+                self.parse_memory = memory;
+            }
+            Event::Solved(part) => self.parts.push(part),
+            Event::Finished(Ok(())) => self.status = Status::Completed,
+            Event::Finished(Err(error)) => self.status = Status::Failed(error),
+        }
+    }
+
+    /// True when the day finished and every implemented part produced an
+    /// answer.
+    pub fn succeeded(&self) -> bool {
+        matches!(self.status, Status::Completed)
+            && self.parts.iter().all(|part| match &part.answer {
+                Ok(_) => true,
+                Err(error) => error.is::<NotImplemented>(),
+            })
+    }
+}
+
+impl fmt::Display for PartReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.answer {
+            Ok(answer) if self.cached => write!(f, "Part {}: {} (cached)", self.part, answer),
+            Ok(answer) => {
+                write!(f, "Part {}: {} ({:?})", self.part, answer, self.elapsed)?;
+                write_memory(f, self.memory)
+            }
+            Err(error) if error.is::<NotImplemented>() => {
+                write!(f, "Part {}: not implemented", self.part)
+            }
+            Err(error) => write!(f, "Part {}: failed: {:#}", self.part, error),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Completed => write!(f, "completed"),
+            Status::Failed(error) => write!(f, "failed: {:#}", error),
+            Status::TimedOut(limit) => write!(f, "timed out after {:?}", limit),
+        }
+    }
+}
+
+impl fmt::Display for DayReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Day {}", self.day)?;
+        if let Some(read) = self.read {
+            write!(f, "  Read: {:?}", read)?;
+            write_memory(f, self.read_memory)?;
+            writeln!(f)?;
+        }
+        if let Some(parse) = self.parse {
+            write!(f, "  Parse: {:?}", parse)?;
+            write_memory(f, self.parse_memory)?;
+            writeln!(f)?;
+        }
+        for part in &self.parts {
+            writeln!(f, "  {}", part)?;
+            if let Err(error) = &part.answer {
+                write_backtrace(f, error)?;
+            }
+        }
+        if !matches!(self.status, Status::Completed) {
+            writeln!(f, "  {}", self.status)?;
+            if let Status::Failed(error) = &self.status {
+                write_backtrace(f, error)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_memory(f: &mut fmt::Formatter, memory: Option<MemoryUsage>) -> fmt::Result {
+    match memory {
+        Some(memory) => write!(f, " [{}]", memory),
+        None => Ok(()),
+    }
+}
+
+fn write_backtrace(f: &mut fmt::Formatter, error: &Error) -> fmt::Result {
+    if let Some(crash) = error.downcast_ref::<Crash>() {
+        writeln!(f, "    Backtrace:")?;
+        for line in crash.backtrace.lines() {
+            writeln!(f, "    {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::InMemoryReader;
+    use serde_json::json;
+
+    #[test]
+    fn test_finds_registered_day() {
+        assert_eq!(find(4).unwrap().day, 4);
+        assert!(find(0).is_none());
+    }
+
+    #[test]
+    fn test_lists_registered_implementation_first() {
+        let names: Vec<&str> = implementations(6).iter().map(|entry| entry.name).collect();
+
+        assert_eq!(names, vec!["unique", "window"]);
+        assert_eq!(implementations(4).len(), 1);
+        assert!(implementations(0).is_empty());
+    }
+
+    #[test]
+    fn test_finds_implementation_by_name() {
+        let find_implementation =
+            |day, name| find_named(day, name, implementations(day), |entry| entry.name);
+
+        assert_eq!(find_implementation(6, "window").unwrap().name, "window");
+        assert_eq!(find_implementation(4, "default").unwrap().day, 4);
+
+        let error = find_implementation(6, "fast")
+            .map(|entry| entry.name)
+            .unwrap_err();
+        assert!(error.to_string().contains("try one of: unique, window"));
+        assert!(find_implementation(0, "default")
+            .map(|entry| entry.name)
+            .unwrap_err()
+            .is::<NotImplemented>());
+    }
+
+    #[test]
+    fn test_run_day_reports_each_part() {
+        let reader = InMemoryReader::from("2-4,6-8\n2-8,3-7\n6-6,4-6");
+
+        let report = run_day(find(4).unwrap(), &reader, Path::new("some-file.txt"));
+
+        let answers: Vec<_> = report
+            .parts
+            .iter()
+            .map(|part| part.answer.as_ref().unwrap().clone())
+            .collect();
+        assert_eq!(answers, vec![Answer::Number(2), Answer::Number(2)]);
+        assert!(report.succeeded());
+    }
+
+    #[test]
+    fn test_dumps_parsed_models() {
+        let elves = (find(1).unwrap().dump)("1\n2\n\n4").unwrap();
+        let rucksacks = (find(3).unwrap().dump)("abcdbf").unwrap();
+        let pairs = (find(4).unwrap().dump)("2-4,6-8").unwrap();
+        let supplies = (find(5).unwrap().dump)("[A]\n 1 \n\nmove 1 from 1 to 1").unwrap();
+
+        assert_eq!(elves, json!([3, 4]));
+        assert_eq!(rucksacks, json!([{"first": "abc", "second": "dbf"}]));
+        assert_eq!(pairs, json!([[{"min": 2, "max": 4}, {"min": 6, "max": 8}]]));
+        assert_eq!(
+            supplies,
+            json!({
+                "stacks": [["A"]],
+                "instructions": [{"quantity": 1, "from": 0, "to": 0}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_run_day_succeeds_with_missing_part() {
+        let reader = InMemoryReader::from("mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+
+        let report = run_day(find(6).unwrap(), &reader, Path::new("some-file.txt"));
+
+        assert!(report.succeeded());
+        assert_eq!(report.parts[1].to_string(), "Part 2: not implemented");
+    }
+
+    #[test]
+    fn test_run_day_fails_on_invalid_input() {
+        let reader = InMemoryReader::from("1-2");
+
+        let report = run_day(find(4).unwrap(), &reader, Path::new("some-file.txt"));
+
+        assert!(matches!(report.status, Status::Failed(_)));
+        assert!(report.read.is_some());
+        assert!(report.parse.is_none());
+        assert!(!report.succeeded());
+    }
+
+    #[test]
+    fn test_run_input_skips_reading() {
+        let report = run_input(find(6).unwrap(), "mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+
+        assert!(report.read.is_none());
+        assert!(report.parse.is_some());
+        assert_eq!(report.parts[0].answer.as_ref().unwrap(), &Answer::Number(7));
+    }
+
+    /// A day whose second part always panics.
+    struct Panicking;
+
+    impl Solution for Panicking {
+        const DAY: usize = 99;
+        const VERSION: u32 = 1;
+        type Parsed = Vec<usize>;
+
+        fn parse(input: &str) -> Result<Self::Parsed> {
+            Ok(input.lines().map(|line| line.len()).collect())
+        }
+
+        fn part_2(parsed: &Self::Parsed) -> Result<Answer> {
+            Ok(parsed[..3].iter().sum::<usize>().into())
+        }
+    }
+
+    #[test]
+    fn test_run_day_reports_panicking_part() {
+        let reader = InMemoryReader::from("10\n20");
+
+        let report = run_day(
+            &Entry::of::<Panicking>(),
+            &reader,
+            Path::new("some-file.txt"),
+        );
+
+        let error = report.parts[1].answer.as_ref().unwrap_err();
+        let crash = error.downcast_ref::<Crash>().unwrap();
+        assert_eq!((crash.day, crash.part), (99, Some(2)));
+        assert!(crash.message.contains("out of range"));
+        assert!(matches!(report.status, Status::Completed));
+        assert!(!report.succeeded());
+    }
+
+    #[test]
+    fn test_run_day_measures_memory_per_phase() {
+        memory::enable();
+        let reader = InMemoryReader::from("2-4,6-8\n2-8,3-7\n6-6,4-6");
+
+        let report = run_day(find(4).unwrap(), &reader, Path::new("some-file.txt"));
+
+        assert!(report.read_memory.unwrap().allocations > 0);
+        assert!(report.parse_memory.unwrap().bytes > 0);
+        assert!(report.parts[0].memory.is_some());
+        assert!(report.to_string().contains("allocations"));
+    }
+
+    mod run_day_with_limit {
+        use super::*;
+
+        /// Never finishes reading, standing in for a day that hangs.
+        struct HangingReader;
+
+        impl FileReader for HangingReader {
+            fn read_file(&self, _: &Path) -> Result<Vec<String>> {
+                loop {
+                    thread::park();
+                }
+            }
+
+            fn read_bytes(&self, _: &Path) -> Result<Vec<u8>> {
+                loop {
+                    thread::park();
+                }
+            }
+        }
+
+        #[test]
+        fn test_returns_report_when_day_finishes_in_time() {
+            let reader = Arc::new(InMemoryReader::from("2-4,6-8\n2-8,3-7"));
+
+            let report = run_day_with_limit(
+                find(4).unwrap(),
+                reader,
+                PathBuf::from("some-file.txt"),
+                Duration::from_secs(10),
+            );
+
+            assert!(report.succeeded());
+            assert_eq!(report.parts.len(), 2);
+        }
+
+        #[test]
+        fn test_records_wall_time() {
+            let reader = Arc::new(InMemoryReader::from("2-4,6-8"));
+
+            let report = run_day_with_limit(
+                find(4).unwrap(),
+                reader,
+                PathBuf::from("some-file.txt"),
+                Duration::from_secs(10),
+            );
+
+            assert!(report.wall >= report.parse.unwrap());
+        }
+
+        #[test]
+        fn test_times_out_when_day_hangs() {
+            let reader = Arc::new(HangingReader);
+
+            let report = run_day_with_limit(
+                find(4).unwrap(),
+                reader,
+                PathBuf::from("some-file.txt"),
+                Duration::from_millis(50),
+            );
+
+            assert!(matches!(report.status, Status::TimedOut(_)));
+            assert!(!report.succeeded());
+        }
+    }
+
+    mod run_solver {
+        use super::*;
+
+        /// Answers with the length of the input after waiting `delay`.
+        struct Slow {
+            delay: Duration,
+        }
+
+        impl Solver for Slow {
+            fn name(&self) -> &str {
+                "slow"
+            }
+
+            fn run_input(&self, day: usize, input: &str) -> DayReport {
+                thread::sleep(self.delay);
+                let mut report = DayReport::new(day);
+                report.parts.push(PartReport {
+                    part: 1,
+                    answer: Ok(Answer::Number(input.len())),
+                    elapsed: self.delay,
+                    memory: None,
+                    cached: false,
+                });
+                report
+            }
+        }
+
+        #[test]
+        fn test_reads_input_for_solver() {
+            let reader = InMemoryReader::from("abc");
+            let solver = Slow {
+                delay: Duration::ZERO,
+            };
+
+            let report = run_solver(&solver, 7, &reader, Path::new("some-file.txt"));
+
+            assert!(report.succeeded());
+            assert!(report.read.is_some());
+            assert_eq!(report.parts[0].answer.as_ref().unwrap(), &Answer::Number(3));
+        }
+
+        #[test]
+        fn test_fails_when_input_is_missing() {
+            let reader = InMemoryReader::from(std::collections::HashMap::new());
+            let solver = Slow {
+                delay: Duration::ZERO,
+            };
+
+            let report = run_solver(&solver, 7, &reader, Path::new("missing.txt"));
+
+            assert!(matches!(report.status, Status::Failed(_)));
+            assert!(report.parts.is_empty());
+        }
+    }
+
+    mod run_parallel {
+        use super::*;
+
+        #[test]
+        fn test_returns_results_in_entry_order() {
+            let entries: Vec<&'static Entry> = DAYS.iter().collect();
+            let mut days = Vec::new();
+
+            run_parallel(
+                &entries,
+                4,
+                |entry| {
+                    // Finish the earlier days last to shuffle completion order.
+                    thread::sleep(Duration::from_millis(10 * (7 - entry.day) as u64));
+                    entry.day
+                },
+                |day| days.push(day),
+            );
+
+            assert_eq!(days, vec![1, 2, 3, 4, 5, 6]);
+        }
+
+        #[test]
+        fn test_runs_days_at_the_same_time() {
+            let entries: Vec<&'static Entry> = DAYS.iter().collect();
+            let start = Instant::now();
+
+            run_parallel(
+                &entries,
+                6,
+                |_| thread::sleep(Duration::from_millis(100)),
+                |_| {},
+            );
+
+            assert!(start.elapsed() < Duration::from_millis(550));
+        }
+
+        #[test]
+        fn test_handles_more_jobs_than_entries() {
+            let mut count = 0;
+
+            run_parallel(
+                &DAYS.iter().take(1).collect::<Vec<_>>(),
+                8,
+                |_| (),
+                |_| count += 1,
+            );
+
+            assert_eq!(count, 1);
+        }
+    }
+
+    #[cfg(unix)]
+    mod run_day_in_process {
+        use super::*;
+
+        fn shell(script: &str) -> Command {
+            let mut command = Command::new("sh");
+            command.args(["-c", script]);
+            command
+        }
+
+        #[test]
+        fn test_completes_when_child_succeeds() {
+            let report = run_day_in_process(4, shell("echo Day 4; exit 0"), None);
+
+            assert!(report.succeeded());
+            assert_eq!(report.output.unwrap(), "Day 4\n");
+        }
+
+        #[test]
+        fn test_fails_when_child_crashes() {
+            let report = run_day_in_process(4, shell("kill -ABRT $$"), None);
+
+            assert!(matches!(report.status, Status::Failed(_)));
+        }
+
+        #[test]
+        fn test_kills_child_that_runs_too_long() {
+            let report = run_day_in_process(4, shell("sleep 10"), Some(Duration::from_millis(50)));
+
+            assert!(matches!(report.status, Status::TimedOut(_)));
+            assert!(report.wall < Duration::from_secs(5));
+        }
+    }
+}
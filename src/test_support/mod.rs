@@ -0,0 +1,55 @@
+//! Helpers for driving days with example text, for use in downstream tests.
+//!
+//! Enable the `test-support` feature to use these outside this crate.
+use super::files::{InMemoryReader, ReadsInput};
+use super::Day;
+use std::fmt::Debug;
+
+/// Builds a day that reads `example` instead of its puzzle input.
+pub fn day_from_example<D: ReadsInput>(example: &str) -> D {
+    D::with_reader(Box::new(InMemoryReader::from(example)))
+}
+
+/// Runs `day` and panics if it fails or its answer is not `expected`.
+pub fn assert_answer<T, D>(day: &D, expected: T)
+where
+    T: Debug + PartialEq,
+    D: Day<T>,
+{
+    let answer = day.run().expect("Failed to run day");
+    assert_eq!(answer, expected);
+}
+
+/// Runs the day built from `example` and checks its answer.
+pub fn assert_example<T, D>(example: &str, expected: T)
+where
+    T: Debug + PartialEq,
+    D: Day<T> + ReadsInput,
+{
+    assert_answer(&day_from_example::<D>(example), expected);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_4::Day4;
+    use crate::day_6::Day6;
+
+    #[test]
+    fn test_day_from_example_reads_example() {
+        let day: Day4 = day_from_example("2-4,6-8\n2-8,3-7");
+
+        assert_answer(&day, (1, 1));
+    }
+
+    #[test]
+    fn test_assert_example() {
+        assert_example::<_, Day6>("nppdvjthqldpwncqszvftbrmjlhg", 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_example_panics_on_wrong_answer() {
+        assert_example::<_, Day6>("nppdvjthqldpwncqszvftbrmjlhg", 7);
+    }
+}
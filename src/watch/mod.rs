@@ -0,0 +1,137 @@
+use super::runner::DayReport;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Notices changes to files by polling their size and modification time.
+///
+/// Directories are watched one level deep, so files added to or removed from
+/// them count as changes too.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    snapshot: BTreeMap<PathBuf, (Option<SystemTime>, u64)>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let snapshot = take_snapshot(&paths);
+        Self { paths, snapshot }
+    }
+
+    /// True if anything changed since the last call.
+    pub fn changed(&mut self) -> bool {
+        let snapshot = take_snapshot(&self.paths);
+        let changed = snapshot != self.snapshot;
+        self.snapshot = snapshot;
+        changed
+    }
+}
+
+fn take_snapshot(paths: &[PathBuf]) -> BTreeMap<PathBuf, (Option<SystemTime>, u64)> {
+    let mut snapshot = BTreeMap::new();
+    for path in paths {
+        match fs::read_dir(path) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    record(&mut snapshot, &entry.path());
+                }
+            }
+            Err(_) => record(&mut snapshot, path),
+        }
+    }
+    snapshot
+}
+
+fn record(snapshot: &mut BTreeMap<PathBuf, (Option<SystemTime>, u64)>, path: &Path) {
+    if let Ok(metadata) = fs::metadata(path) {
+        snapshot.insert(
+            path.to_path_buf(),
+            (metadata.modified().ok(), metadata.len()),
+        );
+    }
+}
+
+/// What each part produced, as shown to the user.
+pub fn answers(report: &DayReport) -> Vec<(usize, String)> {
+    report
+        .parts
+        .iter()
+        .map(|part| {
+            let answer = match &part.answer {
+                Ok(answer) => answer.to_string(),
+                Err(error) => format!("{:#}", error),
+            };
+            (part.part, answer)
+        })
+        .collect()
+}
+
+/// Describes how each part's answer moved since the previous run.
+pub fn diff(previous: &[(usize, String)], current: &[(usize, String)]) -> Vec<String> {
+    current
+        .iter()
+        .map(|(part, answer)| {
+            match previous
+                .iter()
+                .find(|(previous_part, _)| previous_part == part)
+            {
+                Some((_, previous)) if previous == answer => format!("Part {}: unchanged", part),
+                Some((_, previous)) => format!("Part {}: {} -> {}", part, previous, answer),
+                None => format!("Part {}: new answer {}", part, answer),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_notices_new_and_changed_files() {
+        let dir = tempdir().unwrap();
+        let mut watcher = Watcher::new(vec![dir.path().to_path_buf()]);
+        assert!(!watcher.changed());
+
+        let path = dir.path().join("example_1.txt");
+        File::create(&path).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        writeln!(File::create(&path).unwrap(), "1-2,3-4").unwrap();
+        assert!(watcher.changed());
+    }
+
+    #[test]
+    fn test_notices_removed_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("input.txt");
+        File::create(&path).unwrap();
+        let mut watcher = Watcher::new(vec![path.clone()]);
+
+        fs::remove_file(&path).unwrap();
+
+        assert!(watcher.changed());
+    }
+
+    #[test]
+    fn test_diff_describes_each_part() {
+        let previous = vec![(1, "441".to_string()), (2, "860".to_string())];
+        let current = vec![(1, "441".to_string()), (2, "861".to_string())];
+
+        let result = diff(&previous, &current);
+
+        assert_eq!(result, vec!["Part 1: unchanged", "Part 2: 860 -> 861"]);
+    }
+
+    #[test]
+    fn test_diff_marks_first_answers_as_new() {
+        let result = diff(&[], &[(1, "CMZ".to_string())]);
+
+        assert_eq!(result, vec!["Part 1: new answer CMZ"]);
+    }
+}
@@ -1,12 +1,13 @@
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
+use std::io::Write;
 use std::process::Command;
 
 #[test]
 fn runs_day_0() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("aoc_2022")?;
 
-    cmd.arg("0");
+    cmd.args(["0", "--no-cache"]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("Day 0!"));
@@ -18,9 +19,598 @@ fn runs_day_0() -> Result<(), Box<dyn std::error::Error>> {
 fn invalid_day_number() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("aoc_2022")?;
 
-    cmd.arg("9999");
+    cmd.args(["9999", "--no-cache"]);
     cmd.assert()
         .failure()
         .stderr(predicate::str::contains("not implemented yet"));
     Ok(())
 }
+
+#[test]
+fn runs_day_with_phase_timings() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["4", "--no-cache"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Parse:"))
+        .stdout(predicate::str::contains("Part 1: 441"))
+        .stdout(predicate::str::contains("Part 2: 861"));
+    Ok(())
+}
+
+#[test]
+fn runs_day_with_another_input_file() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input = tempfile::NamedTempFile::new()?;
+    writeln!(
+        input,
+        "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8"
+    )?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.arg("4")
+        .arg("--no-cache")
+        .arg("--input")
+        .arg(input.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Part 1: 2"))
+        .stdout(predicate::str::contains("Part 2: 4"));
+    Ok(())
+}
+
+#[test]
+fn runs_day_against_its_example() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["5", "--example", "--no-cache"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Part 1: CMZ"))
+        .stdout(predicate::str::contains("Answers match"));
+    Ok(())
+}
+
+#[test]
+fn fails_when_answers_differ_from_stored_ones() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let input = dir.path().join("input.txt");
+    std::fs::write(&input, "2-4,6-8\n2-8,3-7\n")?;
+    std::fs::write(dir.path().join("input.answers.toml"), "part_1 = 2\n")?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.arg("4").arg("--no-cache").arg("--input").arg(&input);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Part 1: expected 2, got 1"))
+        .stderr(predicate::str::contains("Wrong answers for days [4]"));
+    Ok(())
+}
+
+#[test]
+fn rejects_input_file_for_several_days() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["all", "--input", "some-file.txt", "--no-cache"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("single day"));
+    Ok(())
+}
+
+#[test]
+fn runs_all_days() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["all", "--no-cache"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Day 1"))
+        .stdout(predicate::str::contains("Day 6"));
+    Ok(())
+}
+
+#[test]
+fn reports_day_that_exceeds_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["6", "--timeout", "0.000001", "--no-cache"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("timed out"))
+        .stdout(predicate::str::contains("got no answer").not())
+        .stderr(predicate::str::contains("Wrong answers").not());
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["6", "--timeout", "0.000001", "--no-cache"])
+        .args(["--isolation", "process"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("timed out"))
+        .stdout(predicate::str::contains("got no answer").not())
+        .stderr(predicate::str::contains("Wrong answers").not());
+    Ok(())
+}
+
+#[test]
+fn reads_day_timeout_from_config() -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = tempfile::NamedTempFile::new()?;
+    writeln!(config, "[days.6]\ntimeout = 0.000001")?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["all", "--no-cache", "--config"])
+        .arg(config.path());
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Day 5: completed"))
+        .stdout(predicate::str::contains("Day 6: timed out"));
+    Ok(())
+}
+
+#[test]
+fn rejects_negative_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["6", "--timeout=-1", "--no-cache"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "-1 is not a positive number of seconds",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+
+    let mut config = tempfile::NamedTempFile::new()?;
+    writeln!(config, "timeout = -2")?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["6", "--no-cache", "--config"]).arg(config.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "-2 is not a positive number of seconds",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+    Ok(())
+}
+
+#[test]
+fn runs_days_in_child_processes() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["all", "--isolation", "process", "--no-cache"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Part 1: 441"))
+        .stdout(predicate::str::contains("Day 6: completed"));
+    Ok(())
+}
+
+#[test]
+fn reports_memory_usage() -> Result<(), Box<dyn std::error::Error>> {
+    let cache_dir = tempfile::tempdir()?;
+    let mut warm = Command::cargo_bin("aoc_2022")?;
+    warm.arg("4").arg("--cache-dir").arg(cache_dir.path());
+    warm.assert().success();
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    // Cached answers carry no memory usage, so `--mem` solves again.
+    cmd.args(["4", "--mem", "--cache-dir"])
+        .arg(cache_dir.path());
+    if cfg!(feature = "mem") {
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("allocations"))
+            .stdout(predicate::str::contains("(cached)").not());
+    } else {
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("`mem` feature"));
+    }
+    Ok(())
+}
+
+#[test]
+fn saves_and_compares_bench_baseline() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+
+    let mut save = Command::cargo_bin("aoc_2022")?;
+    save.args(["bench", "6", "--samples", "3", "--save", "base"])
+        .arg("--baseline-dir")
+        .arg(dir.path());
+    save.assert()
+        .success()
+        .stdout(predicate::str::contains("Day 6 part 1"));
+
+    let mut compare = Command::cargo_bin("aoc_2022")?;
+    compare
+        .args(["bench", "6", "--samples", "3", "--compare", "base"])
+        .args(["--threshold", "100000"])
+        .arg("--baseline-dir")
+        .arg(dir.path());
+    compare
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Day 6 part 1:"))
+        .stdout(predicate::str::contains(" vs "));
+    Ok(())
+}
+
+#[test]
+fn bench_rejects_zero_samples() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["bench", "6", "--samples", "0", "--baseline-dir"])
+        .arg(dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--samples"));
+    assert!(!dir.path().join("last.toml").exists());
+    Ok(())
+}
+
+#[test]
+fn bench_fails_without_baseline() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["bench", "6", "--compare", "missing"])
+        .arg("--baseline-dir")
+        .arg(dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No baseline called missing"));
+    Ok(())
+}
+
+#[test]
+fn runs_days_in_parallel_in_day_order() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["all", "--jobs", "4", "--no-cache"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output)?;
+
+    let positions: Vec<usize> = (1..=6)
+        .map(|day| output.find(&format!("Day {}\n", day)).unwrap())
+        .collect();
+    let mut sorted = positions.clone();
+    sorted.sort();
+    assert_eq!(positions, sorted);
+    Ok(())
+}
+
+#[test]
+fn returns_cached_answers_until_cleared() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let run = || -> Result<Command, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("aoc_2022")?;
+        cmd.args(["4", "--cache-dir"]).arg(dir.path());
+        Ok(cmd)
+    };
+
+    run()?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Parse:"));
+    run()?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Part 1: 441 (cached)"))
+        .stdout(predicate::str::contains("Parse:").not());
+
+    let mut clear = Command::cargo_bin("aoc_2022")?;
+    clear
+        .args(["cache", "clear", "--cache-dir"])
+        .arg(dir.path());
+    clear.assert().success();
+
+    run()?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Parse:"));
+    Ok(())
+}
+
+#[test]
+fn generates_same_input_for_same_seed() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("input.txt");
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["gen", "4", "--seed", "9", "--size", "50", "--output"])
+        .arg(&path);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["gen", "4", "--seed", "9", "--size", "50"]);
+    cmd.assert()
+        .success()
+        .stdout(std::fs::read_to_string(&path)?);
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.arg("4").arg("--no-cache").arg("--input").arg(&path);
+    cmd.assert().success();
+    Ok(())
+}
+
+#[test]
+fn runs_named_implementation() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["6", "--impl", "window", "--no-cache"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Part 1: 1262"));
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["6", "--impl", "fast", "--no-cache"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("try one of: unique, window"));
+    Ok(())
+}
+
+#[test]
+fn crosschecks_implementations() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["crosscheck", "--generated", "5"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Day 4: only one implementation"))
+        .stdout(predicate::str::contains(
+            "Day 6: unique, window agree on 11 inputs",
+        ));
+    Ok(())
+}
+
+#[test]
+fn minimizes_failing_input() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let input = dir.path().join("input.txt");
+    let pairs: Vec<&str> = (0..40)
+        .map(|i| if i == 27 { "3-1,2-4" } else { "2-4,6-8" })
+        .collect();
+    std::fs::write(&input, pairs.join("\n"))?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["minimize", "4", "--until", "error", "--input"])
+        .arg(&input);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Reduced 40 lines to 1"));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("input.min.txt"))?,
+        "3-1,2-4\n"
+    );
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["minimize", "4", "--until", "panic", "--input"])
+        .arg(&input);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("does not fail with panic"));
+    Ok(())
+}
+
+/// Writes a config with a day 6 solver written as a shell script, and one
+/// that always answers 1.
+#[cfg(unix)]
+fn external_solvers_config(dir: &std::path::Path) -> std::io::Result<std::path::PathBuf> {
+    let script = dir.join("day_6.sh");
+    std::fs::write(
+        &script,
+        r#"[ "$2" = 1 ] || exit 0
+awk '{
+  for (i = 4; i <= length($0); i++) {
+    window = substr($0, i - 3, 4); unique = 1
+    for (j = 1; j < 4; j++) if (index(substr(window, j + 1), substr(window, j, 1))) unique = 0
+    if (unique) { print i; exit }
+  }
+}' "$3"
+"#,
+    )?;
+    let config = dir.join("aoc.toml");
+    std::fs::write(
+        &config,
+        format!(
+            "[[solvers]]\nname = \"awk\"\ncommand = [\"sh\", \"{}\"]\ninput = \"path\"\ndays = [6]\n\n\
+             [[solvers]]\nname = \"ones\"\ncommand = [\"echo\", \"1\"]\ndays = [4]\n",
+            script.display()
+        ),
+    )?;
+    Ok(config)
+}
+
+#[cfg(unix)]
+#[test]
+fn verifies_external_solvers() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let config = external_solvers_config(dir.path())?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["verify", "6", "--config"]).arg(&config);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "unique: answers match on 6 inputs",
+        ))
+        .stdout(predicate::str::contains("awk: answers match on 6 inputs"));
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["verify", "all", "--config"]).arg(&config);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("ones: wrong answers"))
+        .stdout(predicate::str::contains(
+            "assets/day_4/input.txt: Part 1: expected 441, got 1",
+        ))
+        .stderr(predicate::str::contains("Wrong answers for days [4]."));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_external_solver_as_day_implementation() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let config = external_solvers_config(dir.path())?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["6", "--impl", "awk", "--no-cache", "--config"])
+        .arg(&config);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Part 1: 1262"))
+        .stdout(predicate::str::contains("Part 2: not implemented"))
+        .stdout(predicate::str::contains("Answers match"));
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["6", "--impl", "awk", "--isolation", "process"])
+        .args(["--no-cache", "--config"])
+        .arg(&config);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Part 1: 1262"));
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["4", "--impl", "ones", "--timeout", "10", "--no-cache"])
+        .arg("--config")
+        .arg(&config);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Part 1: expected 441, got 1"))
+        .stderr(predicate::str::contains("Wrong answers for days [4]."));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn crosschecks_and_benchmarks_external_solvers() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let config = external_solvers_config(dir.path())?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["crosscheck", "6", "--generated", "5", "--config"])
+        .arg(&config);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Day 6: unique, window, awk agree on 11 inputs",
+    ));
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["bench", "6", "--impl", "awk", "--samples", "2", "--config"])
+        .arg(&config);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("part 1"))
+        .stdout(predicate::str::contains("parse").not());
+    Ok(())
+}
+
+#[test]
+fn dumps_parsed_input_as_json() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["5", "--example", "--dump-parsed", "--no-cache"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let supplies: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(supplies["stacks"][1], serde_json::json!(["M", "C", "D"]));
+    assert_eq!(
+        supplies["instructions"][0],
+        serde_json::json!({"quantity": 1, "from": 1, "to": 0})
+    );
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["all", "--dump-parsed", "--no-cache"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let days: serde_json::Value = serde_json::from_slice(&output)?;
+    assert!(days["4"][0][0]["min"].is_number());
+    assert!(days["6"].is_string());
+    Ok(())
+}
+
+#[test]
+fn inspects_input_file() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["inspect", "assets/day_5/example_1.txt"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Lines: 9, 1 blank"))
+        .stdout(predicate::str::contains("4 x move <n> from <n> to <n>"))
+        .stdout(predicate::str::contains("Grid: no"));
+    Ok(())
+}
+
+#[test]
+fn reports_health_of_inputs_and_setup() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.arg("doctor");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "assets/day_5/input.txt: 511 lines",
+        ))
+        .stdout(predicate::str::contains("Checksum matches"))
+        .stdout(predicate::str::contains("Healthy"));
+
+    let mut config = tempfile::NamedTempFile::new()?;
+    writeln!(config, "timeut = 1")?;
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["doctor", "--config"]).arg(config.path());
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Invalid config"))
+        .stderr(predicate::str::contains("Found 1 problems."));
+    Ok(())
+}
+
+#[test]
+fn shows_calendar_of_days() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+
+    cmd.args(["calendar", "--runs", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("| Day 1     .* |"))
+        .stdout(predicate::str::contains("| Day 4     ** |"))
+        .stdout(predicate::str::contains("| Day 6     *. |"))
+        .stdout(predicate::str::contains("| Day 25       |"))
+        .stdout(predicate::str::contains("8 of 50 stars"));
+    Ok(())
+}
+
+#[test]
+fn updates_markdown_report_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let readme = dir.path().join("README.md");
+    std::fs::write(
+        &readme,
+        "# Title\n<!-- report:start -->\nold\n<!-- report:end -->\nafter\n",
+    )?;
+
+    let mut bench = Command::cargo_bin("aoc_2022")?;
+    bench
+        .args(["bench", "6", "--samples", "2", "--baseline-dir"])
+        .arg(dir.path());
+    bench.assert().success();
+
+    let mut cmd = Command::cargo_bin("aoc_2022")?;
+    cmd.args(["report", "--markdown", "--update"])
+        .arg(&readme)
+        .arg("--baseline-dir")
+        .arg(dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Updated the report"));
+
+    let content = std::fs::read_to_string(&readme)?;
+    assert!(content.starts_with("# Title\n<!-- report:start -->\n| Day | Puzzle |"));
+    assert!(content.contains(
+        "| 4 | [Camp Cleanup](https://adventofcode.com/2022/day/4) | 1, 2 | yes | - | - |"
+    ));
+    assert!(content.contains("| 6 | [Tuning Trouble]"));
+    assert!(!content
+        .contains("| 6 | [Tuning Trouble](https://adventofcode.com/2022/day/6) | 1 | yes | - |"));
+    assert!(content.ends_with("<!-- report:end -->\nafter\n"));
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use aoc_2022::expected;
+use aoc_2022::files::FileSystemReader;
+use aoc_2022::runner;
+use aoc_2022::NotImplemented;
+
+/// Runs every day against each `assets/day_N/example_N.txt` and checks the
+/// answers stored next to it, so new examples need no code.
+#[test]
+fn every_example_has_the_expected_answers() {
+    let mut checked = 0;
+    let mut failures = Vec::new();
+    for entry in runner::DAYS {
+        for path in expected::examples(entry.day).unwrap() {
+            let expected = expected::load(&path)
+                .unwrap()
+                .unwrap_or_else(|| panic!("No answers for {}", path.display()));
+            let report = runner::run_day(entry, &FileSystemReader {}, &path);
+            if !report.succeeded() {
+                failures.push(format!("{}: {}", path.display(), report.status));
+            }
+            for mismatch in expected::check(&report, &expected) {
+                failures.push(format!("{}: {}", path.display(), mismatch));
+            }
+            // `check` skips parts that aren't implemented, so an answer
+            // stored for one would never be checked.
+            for part in report.parts.iter().filter(|part| {
+                expected.contains_key(&part.part)
+                    && part
+                        .answer
+                        .as_ref()
+                        .is_err_and(|error| error.is::<NotImplemented>())
+            }) {
+                failures.push(format!(
+                    "{}: answer stored for part {}, which isn't implemented",
+                    path.display(),
+                    part.part
+                ));
+            }
+            checked += 1;
+        }
+    }
+
+    assert!(checked > 0, "No examples found");
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}